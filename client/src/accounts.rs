@@ -0,0 +1,20 @@
+use anchor_lang::{AccountDeserialize, Result};
+use lending_borrowing::{Bank, Protocol, RewardPool, User};
+
+// valida el discriminator antes de deserializar, `data` es la data completa de la cuenta
+
+pub fn deserialize_protocol(mut data: &[u8]) -> Result<Protocol> {
+    Protocol::try_deserialize(&mut data)
+}
+
+pub fn deserialize_bank(mut data: &[u8]) -> Result<Bank> {
+    Bank::try_deserialize(&mut data)
+}
+
+pub fn deserialize_user(mut data: &[u8]) -> Result<User> {
+    User::try_deserialize(&mut data)
+}
+
+pub fn deserialize_reward_pool(mut data: &[u8]) -> Result<RewardPool> {
+    RewardPool::try_deserialize(&mut data)
+}
@@ -0,0 +1,1087 @@
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use lending_borrowing::{
+    accounts as ix_accounts, instruction as ix_data, BankConfigParams, BankStatus, OracleConfig,
+    RewardSide, ID,
+};
+
+use crate::{
+    bank_address, collateral_vault_address, insurance_stake_address, insurance_vault_address,
+    pending_config_address, program_data_address, protocol_address, receipt_mint_address,
+    reward_pool_address, reward_vault_address, treasury_address, user_address,
+};
+
+// un builder por instruccion de lib.rs: los campos son las cuentas que no se pueden
+// derivar y los argumentos, el resto (PDAs, ATAs, programas) se completa solo.
+// las remaining_accounts (health, transfer hooks) se agregan con `with_remaining_accounts`
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+pub fn with_remaining_accounts(mut ix: Instruction, accounts: Vec<AccountMeta>) -> Instruction {
+    ix.accounts.extend(accounts);
+    ix
+}
+
+// cuentas de una posicion para el health check, ver health.rs del programa
+#[derive(Clone, Copy, Debug)]
+pub struct HealthAccounts {
+    pub bank: Pubkey,
+    pub price_update: Pubkey,
+    pub fallback_feed: Option<Pubkey>, // None si el bank no tiene oracle secundario
+}
+
+// tripletas [bank, price_update, fallback_feed] en el orden de User.positions
+pub fn health_account_metas(positions: &[HealthAccounts]) -> Vec<AccountMeta> {
+    positions
+        .iter()
+        .flat_map(|position| {
+            [
+                AccountMeta::new_readonly(position.bank, false),
+                AccountMeta::new_readonly(position.price_update, false),
+                AccountMeta::new_readonly(
+                    position.fallback_feed.unwrap_or(position.price_update),
+                    false,
+                ),
+            ]
+        })
+        .collect()
+}
+
+pub struct InitializeProtocol {
+    pub admin: Pubkey,
+    pub guardian: Pubkey,
+    pub fee_receiver: Pubkey,
+}
+
+impl InitializeProtocol {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::InitializeProtocol {
+                admin: self.admin,
+                program: ID,
+                program_data: program_data_address(),
+                protocol: protocol_address(),
+                system_program: system_program::ID,
+            },
+            ix_data::InitializeProtocol {
+                guardian: self.guardian,
+                fee_receiver: self.fee_receiver,
+            },
+        )
+    }
+}
+
+pub struct UpdateProtocolConfig {
+    pub admin: Pubkey,
+    pub guardian: Pubkey,
+    pub fee_receiver: Pubkey,
+    pub config_delay: i64,
+    pub forbidden_extensions: u64,
+}
+
+impl UpdateProtocolConfig {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::UpdateProtocolConfig {
+                admin: self.admin,
+                protocol: protocol_address(),
+            },
+            ix_data::UpdateProtocolConfig {
+                guardian: self.guardian,
+                fee_receiver: self.fee_receiver,
+                config_delay: self.config_delay,
+                forbidden_extensions: self.forbidden_extensions,
+            },
+        )
+    }
+}
+
+pub struct TransferAdmin {
+    pub admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+impl TransferAdmin {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::TransferAdmin {
+                admin: self.admin,
+                protocol: protocol_address(),
+            },
+            ix_data::TransferAdmin {
+                new_admin: self.new_admin,
+            },
+        )
+    }
+}
+
+pub struct AcceptAdmin {
+    pub pending_admin: Pubkey,
+}
+
+impl AcceptAdmin {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::AcceptAdmin {
+                pending_admin: self.pending_admin,
+                protocol: protocol_address(),
+            },
+            ix_data::AcceptAdmin {},
+        )
+    }
+}
+
+// admin o guardian
+pub struct SetPaused {
+    pub signer: Pubkey,
+    pub paused: u8,
+}
+
+impl SetPaused {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::SetPaused {
+                signer: self.signer,
+                protocol: protocol_address(),
+            },
+            ix_data::SetPaused {
+                paused: self.paused,
+            },
+        )
+    }
+}
+
+pub struct SetBankStatus {
+    pub guardian: Pubkey,
+    pub mint: Pubkey,
+    pub status: BankStatus,
+}
+
+impl SetBankStatus {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::SetBankStatus {
+                guardian: self.guardian,
+                protocol: protocol_address(),
+                bank: bank_address(&self.mint),
+            },
+            ix_data::SetBankStatus {
+                status: self.status,
+            },
+        )
+    }
+}
+
+pub struct InitializeBank {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub liquidation_threshold: u64,
+    pub max_ltv: u64,
+    pub oracle: OracleConfig,
+}
+
+impl InitializeBank {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::InitializeBank {
+                admin: self.admin,
+                protocol: protocol_address(),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                insurance_vault: insurance_vault_address(&self.mint),
+                receipt_mint: receipt_mint_address(&self.mint),
+                collateral_vault: collateral_vault_address(&self.mint),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            ix_data::InitializeBank {
+                liquidation_threshold: self.liquidation_threshold,
+                max_ltv: self.max_ltv,
+                oracle: self.oracle,
+            },
+        )
+    }
+}
+
+pub struct QueueConfigChange {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub params: BankConfigParams,
+}
+
+impl QueueConfigChange {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        build(
+            ix_accounts::QueueConfigChange {
+                admin: self.admin,
+                protocol: protocol_address(),
+                bank,
+                pending_config_change: pending_config_address(&bank),
+                system_program: system_program::ID,
+            },
+            ix_data::QueueConfigChange {
+                params: self.params,
+            },
+        )
+    }
+}
+
+pub struct ExecuteConfigChange {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+}
+
+impl ExecuteConfigChange {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        build(
+            ix_accounts::ExecuteConfigChange {
+                admin: self.admin,
+                protocol: protocol_address(),
+                bank,
+                pending_config_change: pending_config_address(&bank),
+            },
+            ix_data::ExecuteConfigChange {},
+        )
+    }
+}
+
+// la renta del cambio encolado vuelve al admin
+pub struct CancelConfigChange {
+    pub guardian: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+}
+
+impl CancelConfigChange {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::CancelConfigChange {
+                guardian: self.guardian,
+                protocol: protocol_address(),
+                admin: self.admin,
+                pending_config_change: pending_config_address(&bank_address(&self.mint)),
+            },
+            ix_data::CancelConfigChange {},
+        )
+    }
+}
+
+pub struct InitializeUser {
+    pub signer: Pubkey,
+}
+
+impl InitializeUser {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::InitializeUser {
+                signer: self.signer,
+                user: user_address(&self.signer),
+                system_program: system_program::ID,
+            },
+            ix_data::IntializeUser {},
+        )
+    }
+}
+
+// reward_pool: Bank.deposit_reward_pool si el bank tiene rewards de depositos
+pub struct DepositToken {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+    pub price_update: Pubkey,
+    pub fallback_feed: Option<Pubkey>, // None si el bank no tiene oracle secundario
+    pub amount: u64,
+}
+
+impl DepositToken {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::DepositToken {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                receipt_mint: receipt_mint_address(&self.mint),
+                collateral_vault: collateral_vault_address(&self.mint),
+                reward_pool: self.reward_pool,
+                price_update: self.price_update,
+                fallback_feed: self.fallback_feed.unwrap_or(self.price_update),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::DepositToken {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+// con deuda requiere las health accounts como remaining accounts
+pub struct WithdrawToken {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+    pub price_update: Pubkey,
+    pub fallback_feed: Option<Pubkey>, // None si el bank no tiene oracle secundario
+    pub amount: u64,
+}
+
+impl WithdrawToken {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::WithdrawToken {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                receipt_mint: receipt_mint_address(&self.mint),
+                collateral_vault: collateral_vault_address(&self.mint),
+                reward_pool: self.reward_pool,
+                price_update: self.price_update,
+                fallback_feed: self.fallback_feed.unwrap_or(self.price_update),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::WithdrawToken {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+// bloquea receipts desde el ATA del receipt mint del signer
+pub struct LockCollateral {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+    pub shares: u64,
+}
+
+impl LockCollateral {
+    pub fn instruction(&self) -> Instruction {
+        let receipt_mint = receipt_mint_address(&self.mint);
+        build(
+            ix_accounts::LockCollateral {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                bank: bank_address(&self.mint),
+                receipt_mint,
+                user_receipt_account: ata(&self.signer, &receipt_mint, &self.token_program),
+                collateral_vault: collateral_vault_address(&self.mint),
+                reward_pool: self.reward_pool,
+                mint_account: self.mint,
+                token_program: self.token_program,
+            },
+            ix_data::LockCollateral {
+                shares: self.shares,
+            },
+        )
+    }
+}
+
+// con deuda requiere las health accounts como remaining accounts
+pub struct UnlockCollateral {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+    pub shares: u64,
+}
+
+impl UnlockCollateral {
+    pub fn instruction(&self) -> Instruction {
+        let receipt_mint = receipt_mint_address(&self.mint);
+        build(
+            ix_accounts::UnlockCollateral {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                bank: bank_address(&self.mint),
+                receipt_mint,
+                user_receipt_account: ata(&self.signer, &receipt_mint, &self.token_program),
+                collateral_vault: collateral_vault_address(&self.mint),
+                reward_pool: self.reward_pool,
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::UnlockCollateral {
+                shares: self.shares,
+            },
+        )
+    }
+}
+
+// requiere las health accounts como remaining accounts, incluida la del bank si es nueva
+pub struct BorrowToken {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+    pub price_update: Pubkey,
+    pub fallback_feed: Option<Pubkey>, // None si el bank no tiene oracle secundario
+    pub amount: u64,
+}
+
+impl BorrowToken {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::BorrowToken {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                reward_pool: self.reward_pool,
+                price_update: self.price_update,
+                fallback_feed: self.fallback_feed.unwrap_or(self.price_update),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::BorrowToken {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+pub struct RepayToken {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+    pub amount: u64,
+}
+
+impl RepayToken {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::RepayToken {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                reward_pool: self.reward_pool,
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+            },
+            ix_data::RepayToken {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+pub struct CreateRewardPool {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub token_program: Pubkey, // del reward mint
+    pub side: RewardSide,
+    pub emission_rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub funding: u64, // se transfiere desde la ATA del admin
+}
+
+impl CreateRewardPool {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        let reward_pool = reward_pool_address(&bank, self.side);
+        build(
+            ix_accounts::CreateRewardPool {
+                admin: self.admin,
+                protocol: protocol_address(),
+                bank,
+                reward_pool,
+                reward_vault: reward_vault_address(&reward_pool),
+                admin_reward_account: ata(&self.admin, &self.reward_mint, &self.token_program),
+                reward_mint: self.reward_mint,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            ix_data::CreateRewardPool {
+                side: self.side,
+                emission_rate: self.emission_rate,
+                start_time: self.start_time,
+                end_time: self.end_time,
+                funding: self.funding,
+            },
+        )
+    }
+}
+
+pub struct ClaimRewards {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub token_program: Pubkey, // del reward mint
+    pub side: RewardSide,
+}
+
+impl ClaimRewards {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        let reward_pool = reward_pool_address(&bank, self.side);
+        build(
+            ix_accounts::ClaimRewards {
+                signer: self.signer,
+                protocol: protocol_address(),
+                user: user_address(&self.signer),
+                bank,
+                reward_pool,
+                reward_vault: reward_vault_address(&reward_pool),
+                reward_mint: self.reward_mint,
+                user_reward_account: ata(&self.signer, &self.reward_mint, &self.token_program),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::ClaimRewards { side: self.side },
+        )
+    }
+}
+
+// tiene que ir seguido, en la misma transaccion, por el FlashRepay del mismo mint
+pub struct FlashBorrow {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+}
+
+impl FlashBorrow {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::FlashBorrow {
+                signer: self.signer,
+                protocol: protocol_address(),
+                bank: bank_address(&self.mint),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank_token_account: treasury_address(&self.mint),
+                mint_account: self.mint,
+                instructions: sysvar::instructions::ID,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::FlashBorrow {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+pub struct FlashRepay {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl FlashRepay {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::FlashRepay {
+                signer: self.signer,
+                bank: bank_address(&self.mint),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank_token_account: treasury_address(&self.mint),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+            },
+            ix_data::FlashRepay {},
+        )
+    }
+}
+
+pub struct RefreshBank {
+    pub mint: Pubkey,
+}
+
+impl RefreshBank {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::RefreshBank {
+                bank: bank_address(&self.mint),
+            },
+            ix_data::RefreshBank {},
+        )
+    }
+}
+
+// fee_destination tiene que ser una token account de Protocol.fee_receiver
+pub struct CollectFees {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub fee_destination: Pubkey,
+}
+
+impl CollectFees {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::CollectFees {
+                admin: self.admin,
+                protocol: protocol_address(),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                fee_destination: self.fee_destination,
+                mint_account: self.mint,
+                token_program: self.token_program,
+            },
+            ix_data::CollectFees {},
+        )
+    }
+}
+
+// requiere las health accounts del borrower como remaining accounts
+pub struct LiquidatePosition {
+    pub liquidator: Pubkey,
+    pub borrower: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub borrowed_mint: Pubkey,
+    pub collateral_token_program: Pubkey,
+    pub borrowed_token_program: Pubkey,
+    pub collateral_price_update: Pubkey,
+    pub borrowed_price_update: Pubkey,
+    pub collateral_fallback_feed: Option<Pubkey>,
+    pub borrowed_fallback_feed: Option<Pubkey>,
+    pub collateral_reward_pool: Option<Pubkey>,
+    pub borrowed_reward_pool: Option<Pubkey>,
+    pub repay_amount: u64,
+    pub min_collateral_out: u64,
+}
+
+impl LiquidatePosition {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::LiquidatePosition {
+                liquidator: self.liquidator,
+                protocol: protocol_address(),
+                borrower: self.borrower,
+                borrower_user: user_address(&self.borrower),
+                collateral_liquidator_token_account: ata(
+                    &self.liquidator,
+                    &self.collateral_mint,
+                    &self.collateral_token_program,
+                ),
+                borrowed_liquidator_token_account: ata(
+                    &self.liquidator,
+                    &self.borrowed_mint,
+                    &self.borrowed_token_program,
+                ),
+                collateral_bank: bank_address(&self.collateral_mint),
+                borrowed_bank: bank_address(&self.borrowed_mint),
+                collateral_bank_token_account: treasury_address(&self.collateral_mint),
+                borrowed_bank_token_account: treasury_address(&self.borrowed_mint),
+                collateral_receipt_mint: receipt_mint_address(&self.collateral_mint),
+                collateral_vault: collateral_vault_address(&self.collateral_mint),
+                collateral_reward_pool: self.collateral_reward_pool,
+                borrowed_reward_pool: self.borrowed_reward_pool,
+                collateral_mint: self.collateral_mint,
+                borrowed_mint: self.borrowed_mint,
+                collateral_price_update: self.collateral_price_update,
+                borrowed_price_update: self.borrowed_price_update,
+                collateral_fallback_feed: self
+                    .collateral_fallback_feed
+                    .unwrap_or(self.collateral_price_update),
+                borrowed_fallback_feed: self
+                    .borrowed_fallback_feed
+                    .unwrap_or(self.borrowed_price_update),
+                collateral_token_program: self.collateral_token_program,
+                borrowed_token_program: self.borrowed_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::LiquidatePosition {
+                repay_amount: self.repay_amount,
+                min_collateral_out: self.min_collateral_out,
+            },
+        )
+    }
+}
+
+// requiere las health accounts del borrower como remaining accounts
+pub struct SettleBadDebt {
+    pub signer: Pubkey,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub reward_pool: Option<Pubkey>,
+}
+
+impl SettleBadDebt {
+    pub fn instruction(&self) -> Instruction {
+        build(
+            ix_accounts::SettleBadDebt {
+                signer: self.signer,
+                protocol: protocol_address(),
+                borrower: self.borrower,
+                borrower_user: user_address(&self.borrower),
+                bank: bank_address(&self.mint),
+                bank_token_account: treasury_address(&self.mint),
+                insurance_vault: insurance_vault_address(&self.mint),
+                reward_pool: self.reward_pool,
+                mint_account: self.mint,
+                token_program: self.token_program,
+            },
+            ix_data::SettleBadDebt {},
+        )
+    }
+}
+
+pub struct StakeInsurance {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+}
+
+impl StakeInsurance {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        build(
+            ix_accounts::StakeInsurance {
+                signer: self.signer,
+                protocol: protocol_address(),
+                insurance_stake: insurance_stake_address(&bank, &self.signer),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank,
+                bank_token_account: treasury_address(&self.mint),
+                insurance_vault: insurance_vault_address(&self.mint),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::StakeInsurance {
+                amount: self.amount,
+            },
+        )
+    }
+}
+
+pub struct RequestUnstakeInsurance {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub shares: u64,
+}
+
+impl RequestUnstakeInsurance {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        build(
+            ix_accounts::RequestUnstakeInsurance {
+                signer: self.signer,
+                insurance_stake: insurance_stake_address(&bank, &self.signer),
+                bank,
+            },
+            ix_data::RequestUnstakeInsurance {
+                shares: self.shares,
+            },
+        )
+    }
+}
+
+pub struct UnstakeInsurance {
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl UnstakeInsurance {
+    pub fn instruction(&self) -> Instruction {
+        let bank = bank_address(&self.mint);
+        build(
+            ix_accounts::UnstakeInsurance {
+                signer: self.signer,
+                protocol: protocol_address(),
+                insurance_stake: insurance_stake_address(&bank, &self.signer),
+                user_token_account: ata(&self.signer, &self.mint, &self.token_program),
+                bank,
+                bank_token_account: treasury_address(&self.mint),
+                insurance_vault: insurance_vault_address(&self.mint),
+                mint_account: self.mint,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix_data::UnstakeInsurance {},
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+
+    use super::*;
+
+    // discriminator de anchor: sha256("global:<nombre en lib.rs>")[..8]
+    fn discriminator(name: &str) -> [u8; 8] {
+        hash(format!("global:{name}").as_bytes()).to_bytes()[..8]
+            .try_into()
+            .unwrap()
+    }
+
+    fn keys(ix: &Instruction) -> Vec<Pubkey> {
+        ix.accounts.iter().map(|meta| meta.pubkey).collect()
+    }
+
+    #[test]
+    fn deposit_accounts_follow_the_program_order() {
+        let signer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+        let price_update = Pubkey::new_unique();
+
+        let ix = DepositToken {
+            signer,
+            mint,
+            token_program,
+            reward_pool: None,
+            price_update,
+            fallback_feed: None,
+            amount: 5,
+        }
+        .instruction();
+
+        assert_eq!(ix.program_id, ID);
+        assert_eq!(
+            keys(&ix),
+            vec![
+                signer,
+                protocol_address(),
+                user_address(&signer),
+                ata(&signer, &mint, &token_program),
+                bank_address(&mint),
+                treasury_address(&mint),
+                receipt_mint_address(&mint),
+                collateral_vault_address(&mint),
+                ID, // reward pool opcional sin pasar
+                price_update,
+                price_update, // sin fallback se repite el price update
+                mint,
+                token_program,
+                associated_token::ID,
+                system_program::ID,
+            ]
+        );
+
+        let writable: Vec<bool> = ix.accounts.iter().map(|meta| meta.is_writable).collect();
+        assert_eq!(
+            writable,
+            vec![
+                true, false, true, true, true, true, true, true, false, false, false, false, false,
+                false, false
+            ]
+        );
+        assert!(ix.accounts[0].is_signer);
+        assert!(ix.accounts[1..].iter().all(|meta| !meta.is_signer));
+
+        assert_eq!(ix.data[..8], discriminator("deposit_token"));
+        assert_eq!(ix.data[8..], 5u64.to_le_bytes());
+    }
+
+    #[test]
+    fn liquidation_keeps_collateral_and_borrowed_accounts_apart() {
+        let liquidator = Pubkey::new_unique();
+        let borrower = Pubkey::new_unique();
+        let collateral_mint = Pubkey::new_unique();
+        let borrowed_mint = Pubkey::new_unique();
+        let collateral_token_program = Pubkey::new_unique();
+        let borrowed_token_program = Pubkey::new_unique();
+        let collateral_price_update = Pubkey::new_unique();
+        let borrowed_price_update = Pubkey::new_unique();
+        let borrowed_fallback_feed = Pubkey::new_unique();
+        let collateral_reward_pool = Pubkey::new_unique();
+
+        let ix = LiquidatePosition {
+            liquidator,
+            borrower,
+            collateral_mint,
+            borrowed_mint,
+            collateral_token_program,
+            borrowed_token_program,
+            collateral_price_update,
+            borrowed_price_update,
+            collateral_fallback_feed: None,
+            borrowed_fallback_feed: Some(borrowed_fallback_feed),
+            collateral_reward_pool: Some(collateral_reward_pool),
+            borrowed_reward_pool: None,
+            repay_amount: 7,
+            min_collateral_out: 3,
+        }
+        .instruction();
+
+        assert_eq!(
+            keys(&ix),
+            vec![
+                liquidator,
+                protocol_address(),
+                borrower,
+                user_address(&borrower),
+                ata(&liquidator, &collateral_mint, &collateral_token_program),
+                ata(&liquidator, &borrowed_mint, &borrowed_token_program),
+                bank_address(&collateral_mint),
+                bank_address(&borrowed_mint),
+                treasury_address(&collateral_mint),
+                treasury_address(&borrowed_mint),
+                receipt_mint_address(&collateral_mint),
+                collateral_vault_address(&collateral_mint),
+                collateral_reward_pool,
+                ID,
+                collateral_mint,
+                borrowed_mint,
+                collateral_price_update,
+                borrowed_price_update,
+                collateral_price_update,
+                borrowed_fallback_feed,
+                collateral_token_program,
+                borrowed_token_program,
+                associated_token::ID,
+                system_program::ID,
+            ]
+        );
+        assert!(ix.accounts[0].is_signer);
+        assert!(ix.accounts[12].is_writable);
+
+        assert_eq!(ix.data[..8], discriminator("liquidate_position"));
+        assert_eq!(ix.data[8..16], 7u64.to_le_bytes());
+        assert_eq!(ix.data[16..], 3u64.to_le_bytes());
+    }
+
+    #[test]
+    fn builders_use_the_program_discriminators() {
+        let signer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+
+        let instructions = [
+            (InitializeUser { signer }.instruction(), "intialize_user"),
+            (SetPaused { signer, paused: 1 }.instruction(), "set_paused"),
+            (
+                ExecuteConfigChange {
+                    admin: signer,
+                    mint,
+                }
+                .instruction(),
+                "execute_config_change",
+            ),
+            (
+                RepayToken {
+                    signer,
+                    mint,
+                    token_program,
+                    reward_pool: None,
+                    amount: 1,
+                }
+                .instruction(),
+                "repay_token",
+            ),
+            (
+                FlashRepay {
+                    signer,
+                    mint,
+                    token_program,
+                }
+                .instruction(),
+                "flash_repay",
+            ),
+            (RefreshBank { mint }.instruction(), "refresh_bank"),
+            (
+                StakeInsurance {
+                    signer,
+                    mint,
+                    token_program,
+                    amount: 1,
+                }
+                .instruction(),
+                "stake_insurance",
+            ),
+            (
+                UnstakeInsurance {
+                    signer,
+                    mint,
+                    token_program,
+                }
+                .instruction(),
+                "unstake_insurance",
+            ),
+        ];
+
+        for (ix, name) in instructions {
+            assert_eq!(ix.program_id, ID, "{name}");
+            assert_eq!(ix.data[..8], discriminator(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn health_accounts_are_triplets_in_position_order() {
+        let first = HealthAccounts {
+            bank: Pubkey::new_unique(),
+            price_update: Pubkey::new_unique(),
+            fallback_feed: Some(Pubkey::new_unique()),
+        };
+        let second = HealthAccounts {
+            bank: Pubkey::new_unique(),
+            price_update: Pubkey::new_unique(),
+            fallback_feed: None,
+        };
+
+        let metas = health_account_metas(&[first, second]);
+
+        assert_eq!(
+            metas.iter().map(|meta| meta.pubkey).collect::<Vec<_>>(),
+            vec![
+                first.bank,
+                first.price_update,
+                first.fallback_feed.unwrap(),
+                second.bank,
+                second.price_update,
+                second.price_update,
+            ]
+        );
+        assert!(metas
+            .iter()
+            .all(|meta| !meta.is_writable && !meta.is_signer));
+    }
+}
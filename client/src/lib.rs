@@ -0,0 +1,17 @@
+//! Off-chain client for the lending-borrowing program: PDA helpers, typed
+//! instruction builders, account deserializers and offline quoting that reuses
+//! the on-chain math.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use accounts::*;
+pub use pda::*;
+pub use quote::*;
+
+pub use lending_borrowing::{
+    BankConfigParams, BankStatus, FallbackOracleConfig, InterestRateModel, OracleConfig,
+    OracleType, RewardSide, ID as PROGRAM_ID,
+};
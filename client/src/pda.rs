@@ -0,0 +1,121 @@
+use anchor_lang::{prelude::Pubkey, solana_program::bpf_loader_upgradeable};
+use lending_borrowing::{RewardSide, ID};
+
+// las seeds tienen que coincidir con las de los #[derive(Accounts)] del programa
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+// cuenta ProgramData del loader upgradeable, initialize_protocol la usa para
+// validar la upgrade authority
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+pub fn protocol_address() -> Pubkey {
+    find(&[b"protocol"])
+}
+
+pub fn bank_address(mint: &Pubkey) -> Pubkey {
+    find(&[mint.as_ref()])
+}
+
+pub fn treasury_address(mint: &Pubkey) -> Pubkey {
+    find(&[b"treasury", mint.as_ref()])
+}
+
+pub fn insurance_vault_address(mint: &Pubkey) -> Pubkey {
+    find(&[b"insurance", mint.as_ref()])
+}
+
+pub fn receipt_mint_address(mint: &Pubkey) -> Pubkey {
+    find(&[b"receipt", mint.as_ref()])
+}
+
+pub fn collateral_vault_address(mint: &Pubkey) -> Pubkey {
+    find(&[b"collateral", mint.as_ref()])
+}
+
+pub fn user_address(owner: &Pubkey) -> Pubkey {
+    find(&[owner.as_ref()])
+}
+
+pub fn pending_config_address(bank: &Pubkey) -> Pubkey {
+    find(&[b"pending_config", bank.as_ref()])
+}
+
+pub fn insurance_stake_address(bank: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"insurance_stake", bank.as_ref(), owner.as_ref()])
+}
+
+pub fn reward_pool_address(bank: &Pubkey, side: RewardSide) -> Pubkey {
+    find(&[b"reward_pool", bank.as_ref(), &[side as u8]])
+}
+
+pub fn reward_vault_address(reward_pool: &Pubkey) -> Pubkey {
+    find(&[b"reward_vault", reward_pool.as_ref()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_use_the_program_seeds() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &ID).0;
+
+        assert_eq!(protocol_address(), pda(&[b"protocol"]));
+        assert_eq!(bank_address(&mint), pda(&[mint.as_ref()]));
+        assert_eq!(treasury_address(&mint), pda(&[b"treasury", mint.as_ref()]));
+        assert_eq!(
+            insurance_vault_address(&mint),
+            pda(&[b"insurance", mint.as_ref()])
+        );
+        assert_eq!(
+            receipt_mint_address(&mint),
+            pda(&[b"receipt", mint.as_ref()])
+        );
+        assert_eq!(
+            collateral_vault_address(&mint),
+            pda(&[b"collateral", mint.as_ref()])
+        );
+        assert_eq!(user_address(&owner), pda(&[owner.as_ref()]));
+
+        let bank = bank_address(&mint);
+        assert_eq!(
+            pending_config_address(&bank),
+            pda(&[b"pending_config", bank.as_ref()])
+        );
+        assert_eq!(
+            insurance_stake_address(&bank, &owner),
+            pda(&[b"insurance_stake", bank.as_ref(), owner.as_ref()])
+        );
+
+        let reward_pool = reward_pool_address(&bank, RewardSide::Borrow);
+        assert_eq!(reward_pool, pda(&[b"reward_pool", bank.as_ref(), &[1]]));
+        assert_eq!(
+            reward_vault_address(&reward_pool),
+            pda(&[b"reward_vault", reward_pool.as_ref()])
+        );
+    }
+
+    #[test]
+    fn reward_pools_are_per_side() {
+        let bank = bank_address(&Pubkey::new_unique());
+        assert_ne!(
+            reward_pool_address(&bank, RewardSide::Deposit),
+            reward_pool_address(&bank, RewardSide::Borrow)
+        );
+    }
+
+    #[test]
+    fn program_data_is_derived_by_the_upgradeable_loader() {
+        let (expected, _) =
+            Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID);
+        assert_eq!(program_data_address(), expected);
+        assert_ne!(program_data_address(), find(&[ID.as_ref()]));
+    }
+}
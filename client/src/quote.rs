@@ -0,0 +1,283 @@
+use anchor_lang::{prelude::Pubkey, Result};
+use lending_borrowing::{
+    debt_price, value_to_amount, Bank, DappError, Health, Position, RewardPool, RewardSide,
+    Rounding, User,
+};
+use pyth_solana_receiver_sdk::price_update::Price;
+
+// cotizaciones offline con la misma matematica que el programa. los precios ya tienen
+// que estar validados (staleness, confianza, fallback) por quien llama
+
+// bank con sus indices llevados a `now`, como lo veria la proxima instruccion
+pub fn accrued_bank(bank: &Bank, now: i64) -> Result<Bank> {
+    let mut bank = bank.clone();
+    bank.accrue_interest(now)?;
+    Ok(bank)
+}
+
+// (depositado, adeudado) de una posicion en unidades del token
+pub fn position_amounts(bank: &Bank, position: &Position, now: i64) -> Result<(u64, u64)> {
+    let bank = accrued_bank(bank, now)?;
+    Ok((
+        bank.deposit_shares_to_amount(position.deposit_shares)?,
+        bank.borrow_shares_to_amount(position.borrow_shares)?,
+    ))
+}
+
+// health de todas las posiciones del usuario, `banks` = (address, bank, precio)
+pub fn quote_health(user: &User, banks: &[(Pubkey, Bank, Price)], now: i64) -> Result<Health> {
+    let mut health = Health::default();
+
+    for position in &user.positions {
+        let (_, bank, price) = banks
+            .iter()
+            .find(|(address, _, _)| *address == position.bank)
+            .ok_or(DappError::InvalidHealthAccounts)?;
+
+        health.add_position(&accrued_bank(bank, now)?, position, price)?;
+    }
+
+    Ok(health)
+}
+
+// maximo que se puede pedir prestado del bank sin quedar por debajo de max_ltv
+pub fn max_borrow_amount(health: &Health, bank: &Bank, price: &Price) -> Result<u64> {
+    if !health.is_healthy() {
+        return Ok(0);
+    }
+
+    let available = health.collateral_value.checked_sub(health.debt_value)?;
+    value_to_amount(
+        available,
+        bank.mint_decimals,
+        &debt_price(price),
+        Rounding::Down,
+    )
+}
+
+// rewards sin cobrar de la posicion si se hiciera claim en `now`
+pub fn pending_rewards(
+    reward_pool: &RewardPool,
+    bank: &Bank,
+    position: &Position,
+    now: i64,
+) -> Result<u64> {
+    let mut reward_pool = reward_pool.clone();
+    reward_pool.update(now, bank.total_shares(reward_pool.side))?;
+
+    let mut position = *position;
+    position.settle_rewards(reward_pool.side, reward_pool.acc_reward_per_share)?;
+
+    Ok(match reward_pool.side {
+        RewardSide::Deposit => position.unclaimed_deposit_rewards,
+        RewardSide::Borrow => position.unclaimed_borrow_rewards,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lending_borrowing::{
+        BankStatus, InterestRateModel, OracleConfig, OracleType, Wad, SECONDS_PER_YEAR, WAD,
+    };
+
+    // bank con indices en 1, 75% ltv y 80% threshold
+    fn bank(mint_decimals: u8, deposited: u64, borrowed: u64) -> Bank {
+        Bank {
+            mint_address: Pubkey::new_unique(),
+            mint_decimals,
+            total_deposited: deposited,
+            total_deposit_shares: deposited,
+            total_borrowed: borrowed,
+            total_borrow_shares: borrowed,
+            liquidation_threshold: 8_000,
+            liquidation_bonus: 500,
+            liquidation_close_factor: 5_000,
+            max_ltv: 7_500,
+            last_updated: 0,
+            cumulative_borrow_index: WAD,
+            cumulative_supply_index: WAD,
+            // 10% fijo, sin importar la utilizacion
+            rate_model: InterestRateModel {
+                base_rate: 1_000,
+                optimal_utilization: 8_000,
+                slope1: 0,
+                slope2: 0,
+            },
+            reserve_factor: 1_000,
+            accumulated_protocol_fees: 0,
+            insurance_fee: 0,
+            accumulated_insurance_fees: 0,
+            total_insurance_shares: 0,
+            insurance_epoch: 0,
+            insurance_cooldown: 0,
+            insurance_unstake_window: 0,
+            oracle: OracleConfig {
+                feed_id: [0; 32],
+                oracle_type: OracleType::PythPull,
+                max_staleness: 60,
+                max_confidence: 200,
+                fallback: None,
+            },
+            status: BankStatus::Active,
+            deposit_limit: 0,
+            borrow_limit: 0,
+            flash_loan_fee: 9,
+            flash_loan_amount: 0,
+            deposit_reward_pool: Pubkey::default(),
+            borrow_reward_pool: Pubkey::default(),
+            unrecovered_bad_debt: 0,
+            liquidity: deposited - borrowed,
+        }
+    }
+
+    // precio con exponente -8, como los feeds de pyth en USD
+    fn price(usd_cents: i64, conf_cents: u64) -> Price {
+        Price {
+            price: usd_cents * 1_000_000,
+            conf: conf_cents * 1_000_000,
+            exponent: -8,
+            publish_time: 0,
+        }
+    }
+
+    fn position(bank: Pubkey, deposit_shares: u64, borrow_shares: u64) -> Position {
+        Position {
+            bank,
+            deposit_shares,
+            borrow_shares,
+            ..Position::default()
+        }
+    }
+
+    fn usd(value: u128) -> Wad {
+        Wad(value * WAD)
+    }
+
+    // 10 SOL de colateral a $150 y 500 USDC de deuda
+    fn sol_usdc_user(
+        sol_conf_cents: u64,
+        usdc_conf_cents: u64,
+    ) -> (User, Vec<(Pubkey, Bank, Price)>) {
+        let sol = (Pubkey::new_unique(), bank(9, 10_000_000_000, 0));
+        let usdc = (Pubkey::new_unique(), bank(6, 1_000_000_000, 500_000_000));
+        let user = User {
+            owner: Pubkey::new_unique(),
+            positions: vec![
+                position(sol.0, 10_000_000_000, 0),
+                position(usdc.0, 0, 500_000_000),
+            ],
+        };
+        let banks = vec![
+            (sol.0, sol.1, price(15_000, sol_conf_cents)),
+            (usdc.0, usdc.1, price(100, usdc_conf_cents)),
+        ];
+        (user, banks)
+    }
+
+    #[test]
+    fn quote_health_values_collateral_and_debt() {
+        let (user, banks) = sol_usdc_user(0, 0);
+        let health = quote_health(&user, &banks, 0).unwrap();
+
+        // $1500 de colateral: 75% para borrow, 80% para liquidacion
+        assert_eq!(health.collateral_value, usd(1_125));
+        assert_eq!(health.liquidation_collateral_value, usd(1_200));
+        assert_eq!(health.debt_value, usd(500));
+        assert!(health.is_healthy());
+        assert_eq!(
+            health.health_factor().unwrap(),
+            Wad::from_ratio(1_200, 500, Rounding::Down).unwrap()
+        );
+
+        // (1125 - 500) USDC
+        let (_, usdc, usdc_price) = &banks[1];
+        assert_eq!(
+            max_borrow_amount(&health, usdc, usdc_price).unwrap(),
+            625_000_000
+        );
+    }
+
+    #[test]
+    fn quote_health_applies_the_price_confidence() {
+        // SOL a $150 +- $1 y USDC a $1 +- $0.01
+        let (user, banks) = sol_usdc_user(100, 1);
+        let health = quote_health(&user, &banks, 0).unwrap();
+
+        // colateral a $149 y deuda a $1.01
+        assert_eq!(health.collateral_value, Wad(1_117_500_000_000_000_000_000));
+        assert_eq!(health.debt_value, usd(505));
+
+        // (1117.5 - 505) / 1.01 USDC, redondeado hacia abajo
+        let (_, usdc, usdc_price) = &banks[1];
+        assert_eq!(
+            max_borrow_amount(&health, usdc, usdc_price).unwrap(),
+            606_435_643
+        );
+    }
+
+    #[test]
+    fn max_borrow_is_zero_when_unhealthy() {
+        let (user, mut banks) = sol_usdc_user(0, 0);
+        // SOL a $65: 10 * 65 * 75% = $487.5 < $500 de deuda < 10 * 65 * 80% = $520
+        banks[0].2 = price(6_500, 0);
+        let health = quote_health(&user, &banks, 0).unwrap();
+
+        assert!(!health.is_healthy());
+        assert!(!health.is_liquidatable());
+        let (_, usdc, usdc_price) = &banks[1];
+        assert_eq!(max_borrow_amount(&health, usdc, usdc_price).unwrap(), 0);
+    }
+
+    #[test]
+    fn quote_health_requires_every_position_bank() {
+        let (user, banks) = sol_usdc_user(0, 0);
+        assert!(quote_health(&user, &banks[..1], 0).is_err());
+    }
+
+    #[test]
+    fn position_amounts_accrue_interest() {
+        // 1000 USDC depositados, 500 prestados, 10% anual durante un periodo de 365 dias
+        let usdc = bank(6, 1_000_000_000, 500_000_000);
+        let (deposited, borrowed) = position_amounts(
+            &usdc,
+            &position(Pubkey::default(), 100_000_000, 100_000_000),
+            SECONDS_PER_YEAR as i64,
+        )
+        .unwrap();
+
+        // deuda: 100 * e^0.1 = 110.517091...
+        assert!((110_517_091..=110_517_092).contains(&borrowed));
+        // interes total 500 * (e^0.1 - 1) = 52.585459, el 90% va a los depositantes:
+        // 100 + 100 / 1000 * 47.326913 = 104.732691
+        assert!((104_732_690..=104_732_692).contains(&deposited));
+    }
+
+    #[test]
+    fn pending_rewards_follow_the_emission_schedule() {
+        let usdc = bank(6, 1_000, 0);
+        let reward_pool = RewardPool {
+            bank: Pubkey::default(),
+            side: RewardSide::Deposit,
+            reward_mint: Pubkey::default(),
+            emission_rate: 10,
+            start_time: 0,
+            end_time: 1_000,
+            last_updated: 0,
+            acc_reward_per_share: 0,
+            total_allocated: 10_000,
+            total_claimed: 0,
+        };
+        // 250 de 1000 shares -> 25% de las emisiones
+        let position = position(Pubkey::default(), 250, 0);
+
+        assert_eq!(
+            pending_rewards(&reward_pool, &usdc, &position, 100).unwrap(),
+            250
+        );
+        assert_eq!(
+            pending_rewards(&reward_pool, &usdc, &position, 5_000).unwrap(),
+            2_500
+        );
+    }
+}
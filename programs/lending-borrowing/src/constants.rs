@@ -2,13 +2,95 @@ use anchor_lang::prelude::*;
 
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
 
-// See https://pyth.network/developers/price-feed-ids for all available IDs.
+// cantidad maxima de banks distintos en los que un usuario puede tener posicion
+pub const MAX_POSITIONS: usize = 8;
+
+// modelo de tasas por defecto, en basis points anuales
+#[constant]
+pub const DEFAULT_BASE_RATE: u64 = 0;
+
+#[constant]
+pub const DEFAULT_OPTIMAL_UTILIZATION: u64 = 8_000; // 80%
+
+#[constant]
+pub const DEFAULT_SLOPE1: u64 = 400; // 4% en la utilizacion optima
+
+#[constant]
+pub const DEFAULT_SLOPE2: u64 = 7_500; // +75% con utilizacion 100%
+
+// tope de la tasa anual con utilizacion 100% (base + slope1 + slope2), evita que
+// compound_factor desborde y deje al bank sin poder acumular interes
+#[constant]
+pub const MAX_BORROW_RATE: u64 = 50_000; // 500%
+
+#[constant]
+pub const DEFAULT_RESERVE_FACTOR: u64 = 1_000; // 10%
+
+// parametros de liquidacion por defecto, en basis points
+#[constant]
+pub const DEFAULT_LIQUIDATION_BONUS: u64 = 500; // 5%
+
+#[constant]
+pub const DEFAULT_LIQUIDATION_CLOSE_FACTOR: u64 = 5_000; // 50%
+
+#[constant]
+pub const MAX_LIQUIDATION_BONUS: u64 = 2_000; // 20%
+
+// porcentaje de los fees del protocolo que va al insurance fund, en basis points
+#[constant]
+pub const DEFAULT_INSURANCE_FEE: u64 = 5_000; // 50%
+
+#[constant]
+pub const DEFAULT_INSURANCE_COOLDOWN: i64 = 7 * 24 * 60 * 60; // 7 dias
+
+// tiempo despues del cooldown para ejecutar el unstake, despues hay que pedirlo de nuevo
+#[constant]
+pub const DEFAULT_INSURANCE_UNSTAKE_WINDOW: i64 = 2 * 24 * 60 * 60; // 2 dias
+
+// fee de los flash loans, en basis points
+#[constant]
+pub const DEFAULT_FLASH_LOAN_FEE: u64 = 9; // 0.09%
+
+#[constant]
+pub const MAX_FLASH_LOAN_FEE: u64 = 100; // 1%
+
+// piso del supply index al socializar bad debt, con 0 amount_to_deposit_shares dividiria por cero
+#[constant]
+pub const MIN_SUPPLY_INDEX: u128 = 1_000_000_000_000; // 1e-6 en WAD
+
+// colateral (valuado a liquidation_threshold, en USD como WAD) que settle_bad_debt trata como polvo
+#[constant]
+pub const MAX_BAD_DEBT_COLLATERAL_VALUE: u128 = 10_000_000_000_000_000; // $0.01
+
+// timelock por defecto para cambios de parametros de riesgo
+#[constant]
+pub const DEFAULT_CONFIG_DELAY: i64 = 2 * 24 * 60 * 60; // 2 dias
+
+// el admin no puede bajar el timelock por debajo de este valor
+#[constant]
+pub const MIN_CONFIG_DELAY: i64 = 24 * 60 * 60; // 1 dia
+
+// extensiones de token-2022 rechazadas por defecto en initialize_bank, bit n = ExtensionType n
+#[constant]
+pub const DEFAULT_FORBIDDEN_EXTENSIONS: u64 = (1 << 9) // NonTransferable
+    | (1 << 12); // PermanentDelegate
+
+// bits de pausa global en Protocol.paused, uno por operacion
+#[constant]
+pub const PAUSE_DEPOSIT: u8 = 1 << 0;
+
+#[constant]
+pub const PAUSE_WITHDRAW: u8 = 1 << 1;
+
+#[constant]
+pub const PAUSE_BORROW: u8 = 1 << 2;
+
 #[constant]
-pub const SOL_USD_FEED_ID: &str = "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE";
+pub const PAUSE_REPAY: u8 = 1 << 3;
 
 #[constant]
-pub const USDC_USD_FEED_ID: &str = "Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX";
+pub const PAUSE_LIQUIDATE: u8 = 1 << 4;
 
-// get_price_no_older_than will fail if the price update is more than 30 seconds old (pyth oracle)
 #[constant]
-pub const MAX_AGE: u64 = 30;
+pub const PAUSE_ALL: u8 =
+    PAUSE_DEPOSIT | PAUSE_WITHDRAW | PAUSE_BORROW | PAUSE_REPAY | PAUSE_LIQUIDATE;
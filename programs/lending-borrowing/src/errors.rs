@@ -10,4 +10,98 @@ pub enum DappError {
     OverRepayableAmount,
     #[msg("Liquidation threshold is too low")]
     LiquidationThresholdIsTooLow,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("User has no position in this bank")]
+    PositionNotFound,
+    #[msg("User already has the maximum number of positions")]
+    TooManyPositions,
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    #[msg("Invalid interest rate model")]
+    InvalidInterestRateModel,
+    #[msg("Invalid reserve factor")]
+    InvalidReserveFactor,
+    #[msg("Missing or mismatched bank and price accounts for health check")]
+    InvalidHealthAccounts,
+    #[msg("Oracle price must be positive")]
+    InvalidPrice,
+    #[msg("Operation would leave the position below the required health")]
+    UnhealthyPosition,
+    #[msg("There are no protocol fees to collect")]
+    NoFeesToCollect,
+    #[msg("Liquidation repay amount must be greater than zero")]
+    InvalidLiquidationAmount,
+    #[msg("Seized collateral is below the requested minimum")]
+    SlippageExceeded,
+    #[msg("Borrower still has collateral")]
+    BorrowerHasCollateral,
+    #[msg("Borrower has no debt in this bank")]
+    NoBadDebt,
+    #[msg("Invalid insurance fund configuration")]
+    InvalidInsuranceConfig,
+    #[msg("Insurance fund has outstanding shares but no balance")]
+    InsuranceFundDepleted,
+    #[msg("Not enough insurance shares")]
+    InsufficientInsuranceShares,
+    #[msg("There is no pending unstake request")]
+    NoPendingUnstake,
+    #[msg("Unstake cooldown has not elapsed yet")]
+    UnstakeCooldownActive,
+    #[msg("Signer is not authorized to perform this operation")]
+    Unauthorized,
+    #[msg("This operation is paused")]
+    OperationPaused,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("Fee destination is not owned by the protocol fee receiver")]
+    InvalidFeeReceiver,
+    #[msg("Bank is not accepting new deposits or borrows")]
+    BankNotActive,
+    #[msg("Bank is frozen")]
+    BankFrozen,
+    #[msg("Deposit would exceed the bank deposit limit")]
+    DepositLimitExceeded,
+    #[msg("Borrow would exceed the bank borrow limit")]
+    BorrowLimitExceeded,
+    #[msg("Invalid bank risk parameters")]
+    InvalidBankConfig,
+    #[msg("Config delay is below the minimum")]
+    InvalidConfigDelay,
+    #[msg("Queued config change is not executable yet")]
+    ConfigChangeNotReady,
+    #[msg("Oracle price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Oracle price is too old")]
+    PriceTooStale,
+    #[msg("Oracle account does not match the bank configuration")]
+    InvalidOracleAccount,
+    #[msg("Primary and fallback oracle prices diverge too much")]
+    OracleDivergence,
+    #[msg("Mint has an extension that is not allowed by the protocol")]
+    ForbiddenMintExtension,
+    #[msg("Invalid flash loan fee")]
+    InvalidFlashLoanFee,
+    #[msg("Flash borrow must be called directly by the transaction")]
+    FlashLoanCpiNotAllowed,
+    #[msg("Flash borrow has no matching flash repay later in the transaction")]
+    FlashRepayMissing,
+    #[msg("Bank already has a flash loan in progress")]
+    FlashLoanInProgress,
+    #[msg("Bank has no flash loan in progress")]
+    NoFlashLoanInProgress,
+    #[msg("Invalid reward pool parameters")]
+    InvalidRewardPoolConfig,
+    #[msg("Bank already has a reward pool for this side")]
+    RewardPoolAlreadySet,
+    #[msg("Missing or mismatched reward pool account")]
+    InvalidRewardPool,
+    #[msg("Current reward campaign has not ended")]
+    RewardCampaignActive,
+    #[msg("Reward vault does not cover the campaign emissions")]
+    InsufficientRewardFunding,
+    #[msg("Collateral and borrowed mints must be different")]
+    SameLiquidationBank,
+    #[msg("Unstake window has expired, request the unstake again")]
+    UnstakeWindowExpired,
 }
@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+
+use crate::{BankConfigParams, BankStatus, FallbackOracleConfig, RewardSide};
+
+#[event]
+pub struct BadDebtEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub bad_debt: u64,
+    pub covered_by_insurance: u64,
+    pub covered_by_protocol: u64,
+    pub socialized_loss: u64,
+    pub unrecovered: u64,
+    pub unrecovered_bad_debt: u64, // acumulado del bank
+    pub cumulative_supply_index: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BankInitializedEvent {
+    pub bank: Pubkey,
+    pub mint: Pubkey,
+    pub liquidation_threshold: u64,
+    pub max_ltv: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserInitializedEvent {
+    pub user: Pubkey,
+    pub user_account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DepositEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub cumulative_supply_index: u128,
+    pub price: i64, // precio del oracle usado, con price_exponent
+    pub price_exponent: i32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub cumulative_supply_index: u128,
+    pub price: i64, // precio del oracle usado, con price_exponent
+    pub price_exponent: i32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralLockedEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralUnlockedEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardPoolCreatedEvent {
+    pub reward_pool: Pubkey,
+    pub bank: Pubkey,
+    pub side: RewardSide,
+    pub reward_mint: Pubkey,
+    pub emission_rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct RewardsClaimedEvent {
+    pub user: Pubkey,
+    pub reward_pool: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BorrowEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub cumulative_borrow_index: u128,
+    pub price: i64, // precio del oracle usado, con price_exponent
+    pub price_exponent: i32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RepayEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub cumulative_borrow_index: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlashBorrowEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// al repagar, con el fee cobrado
+#[event]
+pub struct FlashLoanEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationEvent {
+    pub liquidator: Pubkey,
+    pub borrower: Pubkey,
+    pub collateral_bank: Pubkey,
+    pub borrowed_bank: Pubkey,
+    pub repay_amount: u64,
+    pub repaid_shares: u64,
+    pub collateral_amount: u64,
+    pub seized_shares: u64,
+    pub collateral_price: i64,
+    pub collateral_price_exponent: i32,
+    pub borrowed_price: i64,
+    pub borrowed_price_exponent: i32,
+    pub cumulative_borrow_index: u128,
+    pub cumulative_supply_index: u128,
+    pub timestamp: i64,
+}
+
+// tasas anuales y utilizacion en WAD
+#[event]
+pub struct RateUpdateEvent {
+    pub bank: Pubkey,
+    pub utilization: u128,
+    pub borrow_rate: u128,
+    pub supply_rate: u128,
+    pub cumulative_borrow_index: u128,
+    pub cumulative_supply_index: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesCollectedEvent {
+    pub bank: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleConfigUpdatedEvent {
+    pub bank: Pubkey,
+    pub feed_id: [u8; 32],
+    pub max_staleness: u64,
+    pub max_confidence: u64,
+    pub fallback: Option<FallbackOracleConfig>, // feed secundario y divergencia maxima
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceConfigUpdatedEvent {
+    pub bank: Pubkey,
+    pub insurance_fee: u64,
+    pub insurance_cooldown: i64,
+    pub insurance_unstake_window: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceStakeEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceUnstakeRequestEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceUnstakeEvent {
+    pub user: Pubkey,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolConfigUpdatedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub guardian: Pubkey,
+    pub fee_receiver: Pubkey,
+    pub config_delay: i64,
+    pub forbidden_extensions: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseUpdatedEvent {
+    pub signer: Pubkey,
+    pub paused: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BankStatusUpdatedEvent {
+    pub bank: Pubkey,
+    pub status: BankStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BankLimitsUpdatedEvent {
+    pub bank: Pubkey,
+    pub deposit_limit: u64,
+    pub borrow_limit: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BankConfigUpdatedEvent {
+    pub bank: Pubkey,
+    pub max_ltv: u64,
+    pub liquidation_threshold: u64,
+    pub liquidation_bonus: u64,
+    pub liquidation_close_factor: u64,
+    pub flash_loan_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueuedEvent {
+    pub bank: Pubkey,
+    pub params: BankConfigParams,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelledEvent {
+    pub bank: Pubkey,
+    pub params: BankConfigParams,
+    pub timestamp: i64,
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2};
+
+use crate::{
+    collateral_price, debt_price, get_bank_price, token_value, Bank, DappError, Position, Rounding,
+    User, Wad,
+};
+
+// cuentas que se esperan en remaining_accounts por cada posicion del usuario, en orden:
+// [bank, price_update, fallback_feed]; fallback_feed puede ser cualquier cuenta si el
+// bank no tiene oracle secundario
+pub const HEALTH_ACCOUNTS_PER_POSITION: usize = 3;
+
+/// Aggregated value of every position of a user, in USD (see valuation.rs).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Health {
+    pub collateral_value: Wad,             // colateral ponderado por max_ltv
+    pub liquidation_collateral_value: Wad, // colateral ponderado por liquidation_threshold
+    pub debt_value: Wad,
+}
+
+impl Health {
+    // borrow y withdraw requieren que la deuda quede cubierta por el colateral a max_ltv
+    pub fn is_healthy(&self) -> bool {
+        self.debt_value <= self.collateral_value
+    }
+
+    pub fn is_liquidatable(&self) -> bool {
+        self.debt_value > self.liquidation_collateral_value
+    }
+
+    // colateral a liquidation_threshold / deuda
+    pub fn health_factor(&self) -> Result<Wad> {
+        if self.debt_value.is_zero() {
+            return Ok(Wad(u128::MAX));
+        }
+        self.liquidation_collateral_value
+            .checked_div(self.debt_value, Rounding::Down)
+    }
+
+    /// Adds `position` valued at `price` (already validated). `bank` must have its
+    /// indices accrued. Pure, so the client quotes use the same valuation.
+    pub fn add_position(&mut self, bank: &Bank, position: &Position, price: &Price) -> Result<()> {
+        if position.deposit_shares > 0 {
+            let deposited = bank.deposit_shares_to_amount(position.deposit_shares)?;
+            let value = token_value(
+                deposited,
+                bank.mint_decimals,
+                &collateral_price(price),
+                Rounding::Down,
+            )?;
+
+            self.collateral_value = self
+                .collateral_value
+                .checked_add(value.checked_mul(Wad::from_bps(bank.max_ltv), Rounding::Down)?)?;
+            self.liquidation_collateral_value = self.liquidation_collateral_value.checked_add(
+                value.checked_mul(Wad::from_bps(bank.liquidation_threshold), Rounding::Down)?,
+            )?;
+        }
+
+        if position.borrow_shares > 0 {
+            let borrowed = bank.borrow_shares_to_amount(position.borrow_shares)?;
+            let value = token_value(
+                borrowed,
+                bank.mint_decimals,
+                &debt_price(price),
+                Rounding::Up,
+            )?;
+
+            self.debt_value = self.debt_value.checked_add(value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Values every position of `user` using the `[bank, price_update, fallback_feed]` triplets in
+/// `remaining_accounts`. Banks already loaded as mutable accounts by the
+/// instruction must be passed in `loaded_banks`, since the copies in
+/// `remaining_accounts` would be stale.
+pub fn compute_health(
+    user: &User,
+    remaining_accounts: &[AccountInfo],
+    loaded_banks: &[(Pubkey, &Bank)],
+    clock: &Clock,
+) -> Result<Health> {
+    require!(
+        remaining_accounts.len() >= user.positions.len() * HEALTH_ACCOUNTS_PER_POSITION,
+        DappError::InvalidHealthAccounts
+    );
+
+    let mut health = Health::default();
+
+    for (position, accounts) in user
+        .positions
+        .iter()
+        .zip(remaining_accounts.chunks_exact(HEALTH_ACCOUNTS_PER_POSITION))
+    {
+        let bank_info = &accounts[0];
+        let price_info = &accounts[1];
+        let fallback_info = &accounts[2];
+        require_keys_eq!(
+            bank_info.key(),
+            position.bank,
+            DappError::InvalidHealthAccounts
+        );
+
+        let bank = match loaded_banks.iter().find(|(key, _)| *key == position.bank) {
+            Some((_, bank)) => (*bank).clone(),
+            None => {
+                // solo lectura, se actualizan los indices en memoria
+                let mut bank: Bank = load_account(bank_info)?;
+                bank.accrue_interest(clock.unix_timestamp)?;
+                bank
+            }
+        };
+
+        let price_update: PriceUpdateV2 = load_account(price_info)?;
+        let price = get_bank_price(&bank, &price_update, fallback_info, clock)?;
+
+        health.add_position(&bank, position, &price)?;
+    }
+
+    Ok(health)
+}
+
+// deserializa una cuenta de remaining_accounts validando owner y discriminator
+fn load_account<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<T> {
+    require_keys_eq!(
+        *info.owner,
+        T::owner(),
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{emit_protocol_config, DappError, Protocol};
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = pending_admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn process_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+
+    protocol.admin = protocol.pending_admin;
+    protocol.pending_admin = Pubkey::default();
+
+    emit_protocol_config(protocol)
+}
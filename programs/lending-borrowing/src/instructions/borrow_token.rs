@@ -1,19 +1,28 @@
+use crate::{
+    compute_health, get_bank_price, settle_position_rewards, transfer_tokens, Bank, BorrowEvent,
+    DappError, Protocol, RewardPool, RewardSide, Rounding, User, PAUSE_BORROW,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{self, Mint, TokenAccount, TokenInterface},
-};
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
-
-use crate::{
-    calculate_accrued_interest, Bank, DappError, User, MAX_AGE, SOL_USD_FEED_ID, USDC_USD_FEED_ID,
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
+// remaining_accounts: [bank, price_update, fallback_feed] por cada posicion del usuario (ver health.rs),
+// incluida la posicion de este bank si es nueva
 #[derive(Accounts)]
 pub struct BorrowToken<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_BORROW) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
     #[account(
         mut,
         seeds = [signer.key().as_ref()],
@@ -33,7 +42,8 @@ pub struct BorrowToken<'info> {
     #[account(
         mut,
         seeds = [mint_account.key().as_ref()],
-        bump
+        bump,
+        constraint = bank.status.allows_increase() @ DappError::BankNotActive
     )]
     pub bank: Account<'info, Bank>,
 
@@ -44,48 +54,70 @@ pub struct BorrowToken<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub mint_account: InterfaceAccount<'info, Mint>,
+    // obligatorio si el bank tiene reward pool de prestamos
+    #[account(
+        mut,
+        address = bank.borrow_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
+    // oracle del bank, el precio se registra en el evento
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+    /// CHECK: validado contra bank.oracle.fallback en get_bank_price
+    pub fallback_feed: UncheckedAccount<'info>,
 
-    // Add this account to any instruction Context that needs price data.
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub mint_account: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn process_borrow_token(ctx: Context<BorrowToken>, amount: u64) -> Result<()> {
+pub fn process_borrow_token<'info>(
+    ctx: Context<'_, '_, '_, 'info, BorrowToken<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
     let user = &mut ctx.accounts.user;
     let bank = &mut ctx.accounts.bank;
-    let price_update = &mut ctx.accounts.price_update;
-
-    let (feed_id, deposited): ([u8; 32], u64) =
-        if ctx.accounts.mint_account.key() == user.usdc_address {
-            (get_feed_id_from_hex(SOL_USD_FEED_ID)?, user.deposited_sol)
-        } else {
-            (get_feed_id_from_hex(USDC_USD_FEED_ID)?, user.deposited_usdc)
-        };
 
     let clock = Clock::get()?;
-    let price = price_update.get_price_no_older_than(&clock, MAX_AGE, &feed_id)?;
+    bank.accrue_interest(clock.unix_timestamp)?;
 
-    let new_value = calculate_accrued_interest(deposited, bank.interest_rate, user.last_updated)?;
-    let total_collateral = price.price as u64 * new_value;
+    // actualizar bank y user
+    // deuda -> redondeo hacia arriba
+    let user_shares = bank.amount_to_borrow_shares(amount, Rounding::Up)?;
+
+    let position = user.get_or_create_position(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Borrow,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        clock.unix_timestamp,
+    )?;
 
-    let borrowable_amount = total_collateral
-        .checked_mul(bank.max_ltv) // probablemente max_ltv en base 1000 (por ej: 750 == 75%)
-        .unwrap()
-        .checked_div(1_000) // normalizar
-        .unwrap();
+    bank.total_borrowed = bank
+        .total_borrowed
+        .checked_add(amount)
+        .ok_or(DappError::MathOverflow)?;
+    bank.total_borrow_shares = bank
+        .total_borrow_shares
+        .checked_add(user_shares)
+        .ok_or(DappError::MathOverflow)?;
+    require!(
+        bank.is_within_borrow_limit(),
+        DappError::BorrowLimitExceeded
+    );
 
-    require!(amount <= borrowable_amount, DappError::OverBorrowableAmount);
+    position.borrow_shares = position
+        .borrow_shares
+        .checked_add(user_shares)
+        .ok_or(DappError::MathOverflow)?;
 
-    msg!(
-        "The price is ({} ± {}) * 10^{}",
-        price.price,
-        price.conf,
-        price.exponent
-    );
+    // la deuda total, incluida la nueva, tiene que quedar cubierta por todo el colateral
+    let health = compute_health(user, ctx.remaining_accounts, &[(bank_key, bank)], &clock)?;
+    require!(health.is_healthy(), DappError::OverBorrowableAmount);
 
     let mint_account_key = ctx.accounts.mint_account.key();
     let signer_seeds: &[&[&[u8]]] = &[&[
@@ -94,40 +126,40 @@ pub fn process_borrow_token(ctx: Context<BorrowToken>, amount: u64) -> Result<()
         &[ctx.bumps.bank_token_account],
     ]];
 
-    token_interface::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::TransferChecked {
-                from: ctx.accounts.bank_token_account.to_account_info(),
-                mint: ctx.accounts.mint_account.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.bank_token_account.to_account_info(),
-            },
-            signer_seeds,
-        ),
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.bank_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.bank_token_account.to_account_info(),
+        ctx.remaining_accounts,
         amount,
-        ctx.accounts.mint_account.decimals,
+        signer_seeds,
     )?;
 
-    // actualizar bank y user
-    let user_shares = if bank.total_borrowed == 0 || bank.total_borrow_shares == 0 {
-        amount // 1:1 si es el primero
-    } else {
-        ((amount as f64 / bank.total_borrowed as f64) * bank.total_borrow_shares as f64) as u64
-    };
-
-    bank.total_borrowed += amount;
-    bank.total_borrow_shares += user_shares;
-    bank.last_updated_borrowed = clock.unix_timestamp;
-
-    user.last_updated_borrowed = clock.unix_timestamp;
-    if ctx.accounts.mint_account.key() == user.usdc_address {
-        user.borrowed_usdc += amount;
-        user.borrowed_usdc_shares += user_shares;
-    } else {
-        user.borrowed_sol += amount;
-        user.borrowed_sol_shares += user_shares;
-    }
+    // liquidez con la que se calcula la utilizacion del proximo periodo
+    ctx.accounts.bank_token_account.reload()?;
+    ctx.accounts
+        .bank
+        .sync_liquidity(ctx.accounts.bank_token_account.amount);
+
+    let price = get_bank_price(
+        &ctx.accounts.bank,
+        &ctx.accounts.price_update,
+        &ctx.accounts.fallback_feed,
+        &clock,
+    )?;
+
+    emit!(BorrowEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        amount,
+        shares: user_shares,
+        cumulative_borrow_index: ctx.accounts.bank.cumulative_borrow_index,
+        price: price.price,
+        price_exponent: price.exponent,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{ConfigChangeCancelledEvent, DappError, PendingConfigChange, Protocol};
+
+// la renta vuelve al admin, que fue quien pago el queue
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        has_one = guardian @ DappError::Unauthorized,
+        has_one = admin,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub admin: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_config".as_ref(), pending_config_change.bank.as_ref()],
+        bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+}
+
+pub fn process_cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+    let pending = &ctx.accounts.pending_config_change;
+
+    emit!(ConfigChangeCancelledEvent {
+        bank: pending.bank,
+        params: pending.params,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
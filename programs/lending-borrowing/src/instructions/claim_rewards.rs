@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    settle_position_rewards, transfer_tokens, Bank, DappError, Protocol, RewardPool, RewardSide,
+    RewardsClaimedEvent, User, PAUSE_WITHDRAW,
+};
+
+// remaining_accounts: cuentas extra del transfer hook del reward_mint, si tiene
+#[derive(Accounts)]
+#[instruction(side: RewardSide)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // cobrar rewards saca tokens del programa, igual que un withdraw
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_WITHDRAW) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        seeds = [bank.mint_address.as_ref()],
+        bump,
+        constraint = bank.status.allows_reduce() @ DappError::BankFrozen
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool".as_ref(), bank.key().as_ref(), &[side as u8]],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault".as_ref(), reward_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = reward_pool.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_claim_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>,
+    side: RewardSide,
+) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
+    let user = &mut ctx.accounts.user;
+    let now = Clock::get()?.unix_timestamp;
+
+    let position = user.position_mut(&bank_key)?;
+    settle_position_rewards(
+        &ctx.accounts.bank,
+        side,
+        Some(&mut ctx.accounts.reward_pool),
+        position,
+        now,
+    )?;
+
+    // si el vault no alcanza se paga lo que haya y el resto queda pendiente
+    let unclaimed = match side {
+        RewardSide::Deposit => &mut position.unclaimed_deposit_rewards,
+        RewardSide::Borrow => &mut position.unclaimed_borrow_rewards,
+    };
+    let amount = (*unclaimed).min(ctx.accounts.reward_vault.amount);
+    *unclaimed -= amount;
+    user.remove_empty_positions();
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.total_claimed = reward_pool
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(DappError::MathOverflow)?;
+
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"reward_vault".as_ref(),
+        reward_pool_key.as_ref(),
+        &[ctx.bumps.reward_vault],
+    ]];
+
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.reward_vault.to_account_info(),
+        &ctx.accounts.reward_mint,
+        ctx.accounts.user_reward_account.to_account_info(),
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    emit!(RewardsClaimedEvent {
+        user: ctx.accounts.signer.key(),
+        reward_pool: reward_pool_key,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
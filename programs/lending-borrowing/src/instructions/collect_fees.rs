@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{transfer_tokens, Bank, DappError, FeesCollectedEvent, Protocol};
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint_account,
+        token::token_program = token_program,
+        constraint = fee_destination.owner == protocol.fee_receiver @ DappError::InvalidFeeReceiver
+    )]
+    pub fee_destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn process_collect_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, CollectFees<'info>>,
+) -> Result<()> {
+    let bank = &mut ctx.accounts.bank;
+
+    let now = Clock::get()?.unix_timestamp;
+    bank.accrue_interest(now)?;
+
+    // solo se puede cobrar lo que hay disponible en el treasury
+    let amount = bank
+        .accumulated_protocol_fees
+        .min(ctx.accounts.bank_token_account.amount);
+    require!(amount > 0, DappError::NoFeesToCollect);
+
+    bank.accumulated_protocol_fees -= amount;
+
+    let mint_key = ctx.accounts.mint_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"treasury".as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.bank_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        ctx.accounts.fee_destination.to_account_info(),
+        ctx.accounts.bank_token_account.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    emit!(FeesCollectedEvent {
+        bank: ctx.accounts.bank.key(),
+        destination: ctx.accounts.fee_destination.key(),
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
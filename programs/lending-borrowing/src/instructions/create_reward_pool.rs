@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    transfer_tokens_in, Bank, DappError, Protocol, RewardPool, RewardPoolCreatedEvent, RewardSide,
+    ANCHOR_DISCRIMINATOR,
+};
+
+// crea el pool de un lado del bank o, si ya existe y su emision termino, arranca un nuevo periodo.
+// remaining_accounts: cuentas extra del transfer hook del reward_mint, si tiene
+#[derive(Accounts)]
+#[instruction(side: RewardSide)]
+pub struct CreateRewardPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [bank.mint_address.as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RewardPool::INIT_SPACE + ANCHOR_DISCRIMINATOR,
+        seeds = [b"reward_pool".as_ref(), bank.key().as_ref(), &[side as u8]],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    // en un nuevo periodo el vault ya existe y fija el reward_mint
+    #[account(
+        init_if_needed,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = reward_vault,
+        token::token_program = token_program,
+        seeds = [b"reward_vault".as_ref(), reward_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = admin,
+        token::token_program = token_program
+    )]
+    pub admin_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// `funding` se transfiere al vault, que despues tiene que cubrir todo lo asignado sin reclamar
+pub fn process_create_reward_pool<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateRewardPool<'info>>,
+    side: RewardSide,
+    emission_rate: u64,
+    start_time: i64,
+    end_time: i64,
+    funding: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        emission_rate > 0 && start_time >= now && end_time > start_time,
+        DappError::InvalidRewardPoolConfig
+    );
+
+    let bank = &mut ctx.accounts.bank;
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    let reward_pool = &mut ctx.accounts.reward_pool;
+
+    if bank.reward_pool(side) == reward_pool_key {
+        require!(now >= reward_pool.end_time, DappError::RewardCampaignActive);
+
+        // cierra el periodo anterior, acc_reward_per_share sigue creciendo desde ahi
+        // para que los indices de las posiciones sigan siendo validos
+        reward_pool.update(now, bank.total_shares(side))?;
+    } else {
+        require_keys_eq!(
+            bank.reward_pool(side),
+            Pubkey::default(),
+            DappError::RewardPoolAlreadySet
+        );
+        match side {
+            RewardSide::Deposit => bank.deposit_reward_pool = reward_pool_key,
+            RewardSide::Borrow => bank.borrow_reward_pool = reward_pool_key,
+        }
+
+        reward_pool.bank = bank.key();
+        reward_pool.side = side;
+        reward_pool.reward_mint = ctx.accounts.reward_mint.key();
+    }
+
+    // las shares existentes ganan desde start_time
+    reward_pool.emission_rate = emission_rate;
+    reward_pool.start_time = start_time;
+    reward_pool.end_time = end_time;
+    reward_pool.last_updated = start_time;
+
+    let campaign_rewards = (emission_rate as u128)
+        .checked_mul((end_time - start_time) as u128)
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(DappError::MathOverflow)?;
+    reward_pool.total_allocated = reward_pool
+        .total_allocated
+        .checked_add(campaign_rewards)
+        .ok_or(DappError::MathOverflow)?;
+
+    if funding > 0 {
+        transfer_tokens_in(
+            &ctx.accounts.token_program,
+            ctx.accounts.admin_reward_account.to_account_info(),
+            &ctx.accounts.reward_mint,
+            &mut ctx.accounts.reward_vault,
+            ctx.accounts.admin.to_account_info(),
+            ctx.remaining_accounts,
+            funding,
+            &[],
+        )?;
+    }
+
+    let reward_pool = &ctx.accounts.reward_pool;
+    require!(
+        ctx.accounts.reward_vault.amount >= reward_pool.outstanding_rewards(),
+        DappError::InsufficientRewardFunding
+    );
+
+    emit!(RewardPoolCreatedEvent {
+        reward_pool: reward_pool_key,
+        bank: reward_pool.bank,
+        side,
+        reward_mint: reward_pool.reward_mint,
+        emission_rate,
+        start_time,
+        end_time,
+    });
+
+    Ok(())
+}
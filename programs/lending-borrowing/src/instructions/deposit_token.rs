@@ -1,16 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{self, Mint, TokenAccount, TokenInterface},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::{Bank, User, ANCHOR_DISCRIMINATOR};
+use crate::{
+    get_bank_price, mint_receipts, settle_position_rewards, transfer_tokens_in, Bank, DappError,
+    DepositEvent, Protocol, RewardPool, RewardSide, Rounding, User, ANCHOR_DISCRIMINATOR,
+    PAUSE_DEPOSIT,
+};
 
 #[derive(Accounts)]
 pub struct DepositToken<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_DEPOSIT) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
     #[account(
         init_if_needed,
         payer = signer,
@@ -31,7 +43,8 @@ pub struct DepositToken<'info> {
     #[account(
         mut,
         seeds = [mint_account.key().as_ref()],
-        bump
+        bump,
+        constraint = bank.status.allows_increase() @ DappError::BankNotActive
     )]
     pub bank: Account<'info, Bank>,
 
@@ -44,6 +57,32 @@ pub struct DepositToken<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"receipt".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // obligatorio si el bank tiene reward pool de depositos
+    #[account(
+        mut,
+        address = bank.deposit_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
+    // oracle del bank, el precio se registra en el evento
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+    /// CHECK: validado contra bank.oracle.fallback en get_bank_price
+    pub fallback_feed: UncheckedAccount<'info>,
+
     pub mint_account: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -51,73 +90,101 @@ pub struct DepositToken<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn process_deposit_token(ctx: Context<DepositToken>, amount: u64) -> Result<()> {
-    // transfer token to bank
-    token_interface::transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::TransferChecked {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                mint: ctx.accounts.mint_account.to_account_info(),
-                to: ctx.accounts.bank_token_account.to_account_info(),
-                authority: ctx.accounts.signer.to_account_info(),
-            },
-        ),
+pub fn process_deposit_token<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositToken<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    ctx.accounts.bank.accrue_interest(now)?;
+
+    // transfer token to bank, con transfer fee se acredita solo lo recibido
+    let amount = transfer_tokens_in(
+        &ctx.accounts.token_program,
+        ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.bank_token_account,
+        ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
         amount,
-        ctx.accounts.mint_account.decimals,
+        &[],
     )?;
 
     // update bank and user info
+    let bank_key = ctx.accounts.bank.key();
     let bank = &mut ctx.accounts.bank;
     let user = &mut ctx.accounts.user;
 
-    if bank.total_deposited == 0 {
-        bank.total_deposit_shares = amount;
-
-        match ctx.accounts.mint_account.key() {
-            key if key == user.usdc_address.key() => {
-                user.deposited_usdc += amount;
-                user.deposited_usdc_shares += amount;
-            }
-            _ => {
-                user.deposited_sol += amount;
-                user.deposited_sol_shares += amount;
-            }
-        }
-    } else {
-        // calcular shares proporcional
-        /*
-        // podria fallar si amount < bank.total_deposited -> deposit_ratio = 0 -> por lo tanto user_shares = 0 -> usuario puede depositar pero recibir 0 shares -> pierde dinero en el protocolo
-        let deposit_ratio = amount.checked_div(bank.total_deposited).unwrap();
-        let user_shares = bank
-            .total_deposit_shares
-            .checked_mul(deposit_ratio)
-            .unwrap();
-        */
-
-        let user_shares = ((amount as u128)
-            .checked_mul(bank.total_deposit_shares as u128)
-            .unwrap()
-            .checked_div(bank.total_deposited as u128)
-            .unwrap()) as u64;
-
-        match ctx.accounts.mint_account.key() {
-            key if key == user.usdc_address.key() => {
-                user.deposited_usdc += amount;
-                user.deposited_usdc_shares += user_shares;
-            }
-            _ => {
-                user.deposited_sol += amount;
-                user.deposited_sol_shares += user_shares;
-            }
-        }
-
-        bank.total_deposit_shares += user_shares;
+    // shares proporcionales -> redondeo hacia abajo, a favor del protocolo
+    let user_shares = bank.amount_to_deposit_shares(amount, Rounding::Down)?;
+
+    if user.owner == Pubkey::default() {
+        user.owner = ctx.accounts.signer.key();
     }
 
-    let timestamp = Clock::get()?.unix_timestamp;
-    bank.last_updated = timestamp;
-    user.last_updated = timestamp;
+    let position = user.get_or_create_position(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Deposit,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        now,
+    )?;
+    position.deposit_shares = position
+        .deposit_shares
+        .checked_add(user_shares)
+        .ok_or(DappError::MathOverflow)?;
+
+    bank.total_deposited = bank
+        .total_deposited
+        .checked_add(amount)
+        .ok_or(DappError::MathOverflow)?;
+    bank.total_deposit_shares = bank
+        .total_deposit_shares
+        .checked_add(user_shares)
+        .ok_or(DappError::MathOverflow)?;
+    require!(
+        bank.is_within_deposit_limit(),
+        DappError::DepositLimitExceeded
+    );
+
+    // las shares de la posicion quedan respaldadas por receipts en el vault de colateral
+    let mint_key = ctx.accounts.mint_account.key();
+    let bank_seeds: &[&[&[u8]]] = &[&[mint_key.as_ref(), &[ctx.bumps.bank]]];
+    mint_receipts(
+        &ctx.accounts.token_program,
+        &ctx.accounts.receipt_mint,
+        ctx.accounts.collateral_vault.to_account_info(),
+        ctx.accounts.bank.to_account_info(),
+        user_shares,
+        bank_seeds,
+    )?;
+
+    let price = get_bank_price(
+        &ctx.accounts.bank,
+        &ctx.accounts.price_update,
+        &ctx.accounts.fallback_feed,
+        &clock,
+    )?;
+
+    // liquidez con la que se calcula la utilizacion del proximo periodo
+    ctx.accounts
+        .bank
+        .sync_liquidity(ctx.accounts.bank_token_account.amount);
+
+    let bank = &ctx.accounts.bank;
+    let user = &ctx.accounts.user;
+
+    emit!(DepositEvent {
+        user: user.owner,
+        bank: bank_key,
+        amount,
+        shares: user_shares,
+        cumulative_supply_index: bank.cumulative_supply_index,
+        price: price.price,
+        price_exponent: price.exponent,
+        timestamp: now,
+    });
 
     Ok(())
 }
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    emit_bank_config, emit_rate_update, Bank, BankLimitsUpdatedEvent, DappError,
+    InsuranceConfigUpdatedEvent, OracleConfigUpdatedEvent, PendingConfigChange, Protocol,
+};
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [bank.mint_address.as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = bank,
+        seeds = [b"pending_config".as_ref(), bank.key().as_ref()],
+        bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+}
+
+pub fn process_execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let params = ctx.accounts.pending_config_change.params;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= ctx.accounts.pending_config_change.executable_at,
+        DappError::ConfigChangeNotReady
+    );
+
+    let bank_key = ctx.accounts.bank.key();
+    let bank = &mut ctx.accounts.bank;
+
+    // el interes y los fees hasta ahora se calculan con la config anterior
+    bank.accrue_interest(now)?;
+
+    // se vuelve a validar, la config pudo cambiar mientras estaba en cola
+    bank.apply_config(&params);
+    bank.validate_config()?;
+
+    emit_bank_config(bank_key, bank)?;
+
+    if params.rate_model.is_some() || params.reserve_factor.is_some() {
+        emit_rate_update(bank_key, bank, now)?;
+    }
+
+    if params.oracle.is_some() {
+        emit!(OracleConfigUpdatedEvent {
+            bank: bank_key,
+            feed_id: bank.oracle.feed_id,
+            max_staleness: bank.oracle.max_staleness,
+            max_confidence: bank.oracle.max_confidence,
+            fallback: bank.oracle.fallback,
+            timestamp: now,
+        });
+    }
+
+    if params.deposit_limit.is_some() || params.borrow_limit.is_some() {
+        emit!(BankLimitsUpdatedEvent {
+            bank: bank_key,
+            deposit_limit: bank.deposit_limit,
+            borrow_limit: bank.borrow_limit,
+            timestamp: now,
+        });
+    }
+
+    if params.insurance_fee.is_some()
+        || params.insurance_cooldown.is_some()
+        || params.insurance_unstake_window.is_some()
+    {
+        emit!(InsuranceConfigUpdatedEvent {
+            bank: bank_key,
+            insurance_fee: bank.insurance_fee,
+            insurance_cooldown: bank.insurance_cooldown,
+            insurance_unstake_window: bank.insurance_unstake_window,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
+}
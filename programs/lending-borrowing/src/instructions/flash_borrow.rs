@@ -0,0 +1,137 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked,
+    },
+    Discriminator,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    instruction, transfer_tokens, Bank, DappError, FlashBorrowEvent, Protocol, PAUSE_BORROW,
+};
+
+// posicion de la cuenta bank en FlashRepay
+pub const FLASH_REPAY_BANK_INDEX: usize = 1;
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_BORROW) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump,
+        constraint = bank.status.allows_increase() @ DappError::BankNotActive
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint_account,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: sysvar de instrucciones, validado por address
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_flash_borrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, FlashBorrow<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
+    let bank = &mut ctx.accounts.bank;
+    require!(bank.flash_loan_amount == 0, DappError::FlashLoanInProgress);
+
+    let now = Clock::get()?.unix_timestamp;
+    bank.accrue_interest(now)?;
+
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)? as usize;
+
+    // via CPI la instruccion actual seria la del otro programa y no se podria
+    // garantizar que el repay llegue en la misma transaccion
+    let current_ix = load_instruction_at_checked(current_index, &instructions)?;
+    require_keys_eq!(
+        current_ix.program_id,
+        crate::ID,
+        DappError::FlashLoanCpiNotAllowed
+    );
+
+    // buscar un flash_repay del mismo bank mas adelante en la transaccion
+    let mut index = current_index + 1;
+    loop {
+        let ix = load_instruction_at_checked(index, &instructions)
+            .map_err(|_| DappError::FlashRepayMissing)?;
+
+        if ix.program_id == crate::ID
+            && ix.data.starts_with(&instruction::FlashRepay::DISCRIMINATOR)
+            && ix
+                .accounts
+                .get(FLASH_REPAY_BANK_INDEX)
+                .is_some_and(|account| account.pubkey == bank_key)
+        {
+            break;
+        }
+
+        index += 1;
+    }
+
+    bank.flash_loan_amount = amount;
+
+    let mint_account_key = ctx.accounts.mint_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"treasury".as_ref(),
+        mint_account_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.bank_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.bank_token_account.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    emit!(FlashBorrowEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
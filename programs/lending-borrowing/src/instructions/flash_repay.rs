@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    mul_div, transfer_tokens_in, Bank, DappError, FlashLoanEvent, Rounding, BPS_DENOMINATOR,
+};
+
+// el orden de las cuentas importa: flash_borrow busca el bank en FLASH_REPAY_BANK_INDEX
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// sin chequeo de pausa ni de status: un flash loan abierto siempre se tiene que poder cerrar
+pub fn process_flash_repay<'info>(
+    ctx: Context<'_, '_, '_, 'info, FlashRepay<'info>>,
+) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
+    let bank = &mut ctx.accounts.bank;
+
+    let amount = bank.flash_loan_amount;
+    require!(amount > 0, DappError::NoFlashLoanInProgress);
+
+    let now = Clock::get()?.unix_timestamp;
+    bank.accrue_interest(now)?;
+
+    let fee = mul_div(amount, bank.flash_loan_fee, BPS_DENOMINATOR, Rounding::Up)?;
+    let total = amount.checked_add(fee).ok_or(DappError::MathOverflow)?;
+
+    // con transfer fee lo recibido tiene que cubrir igual monto + fee
+    let received = transfer_tokens_in(
+        &ctx.accounts.token_program,
+        ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.bank_token_account,
+        ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
+        total,
+        &[],
+    )?;
+    require!(received >= total, DappError::InsufficientBalance);
+
+    let bank = &mut ctx.accounts.bank;
+    bank.flash_loan_amount = 0;
+
+    // el fee se reparte igual que el interes: reserve factor al protocolo, el resto a los
+    // depositantes, que son los que aportan la liquidez del flash loan
+    let protocol_fee = mul_div(fee, bank.reserve_factor, BPS_DENOMINATOR, Rounding::Up)?;
+    bank.add_protocol_fee(protocol_fee)?;
+    bank.credit_depositors(fee - protocol_fee)?;
+
+    // liquidez con la que se calcula la utilizacion del proximo periodo
+    bank.sync_liquidity(ctx.accounts.bank_token_account.amount);
+
+    emit!(FlashLoanEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        amount,
+        fee,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -1,16 +1,29 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{Bank, ANCHOR_DISCRIMINATOR};
+use crate::{
+    check_mint_extensions, Bank, BankInitializedEvent, DappError, InterestRateModel, OracleConfig,
+    Protocol, ANCHOR_DISCRIMINATOR, DEFAULT_BASE_RATE, DEFAULT_FLASH_LOAN_FEE,
+    DEFAULT_INSURANCE_COOLDOWN, DEFAULT_INSURANCE_FEE, DEFAULT_INSURANCE_UNSTAKE_WINDOW,
+    DEFAULT_LIQUIDATION_BONUS, DEFAULT_LIQUIDATION_CLOSE_FACTOR, DEFAULT_OPTIMAL_UTILIZATION,
+    DEFAULT_RESERVE_FACTOR, DEFAULT_SLOPE1, DEFAULT_SLOPE2, WAD,
+};
 
 #[derive(Accounts)]
 pub struct InitializeBank<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
 
     #[account(
         init,
-        payer = authority,
+        payer = admin,
         space = Bank::INIT_SPACE + ANCHOR_DISCRIMINATOR,
         seeds = [mint_account.key().as_ref()],
         bump
@@ -20,7 +33,7 @@ pub struct InitializeBank<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = admin,
         token::mint = mint_account,
         token::authority = bank_token_account,
         seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
@@ -28,6 +41,40 @@ pub struct InitializeBank<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint_account,
+        token::authority = insurance_vault,
+        seeds = [b"insurance".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+
+    // receipts de los depositos, 1 token = 1 deposit share
+    #[account(
+        init,
+        payer = admin,
+        mint::decimals = mint_account.decimals,
+        mint::authority = bank,
+        mint::token_program = token_program,
+        seeds = [b"receipt".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // receipts bloqueados como colateral, respaldan los deposit_shares de las posiciones
+    #[account(
+        init,
+        payer = admin,
+        token::mint = receipt_mint,
+        token::authority = collateral_vault,
+        token::token_program = token_program,
+        seeds = [b"collateral".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
     pub mint_account: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -38,14 +85,45 @@ pub fn process_initialize_bank(
     ctx: Context<InitializeBank>,
     liquidation_threshold: u64,
     max_ltv: u64,
+    oracle: OracleConfig,
 ) -> Result<()> {
+    check_mint_extensions(
+        &ctx.accounts.mint_account,
+        ctx.accounts.protocol.forbidden_extensions,
+    )?;
+
     let bank = &mut ctx.accounts.bank;
 
-    bank.authority = ctx.accounts.authority.key();
     bank.mint_address = ctx.accounts.mint_account.key();
+    bank.mint_decimals = ctx.accounts.mint_account.decimals;
     bank.liquidation_threshold = liquidation_threshold;
     bank.max_ltv = max_ltv;
-    bank.interest_rate = 0.05 as u64; // [?]
+    bank.liquidation_bonus = DEFAULT_LIQUIDATION_BONUS;
+    bank.liquidation_close_factor = DEFAULT_LIQUIDATION_CLOSE_FACTOR;
+    bank.rate_model = InterestRateModel {
+        base_rate: DEFAULT_BASE_RATE,
+        optimal_utilization: DEFAULT_OPTIMAL_UTILIZATION,
+        slope1: DEFAULT_SLOPE1,
+        slope2: DEFAULT_SLOPE2,
+    };
+    bank.reserve_factor = DEFAULT_RESERVE_FACTOR;
+    bank.insurance_fee = DEFAULT_INSURANCE_FEE;
+    bank.insurance_cooldown = DEFAULT_INSURANCE_COOLDOWN;
+    bank.insurance_unstake_window = DEFAULT_INSURANCE_UNSTAKE_WINDOW;
+    bank.flash_loan_fee = DEFAULT_FLASH_LOAN_FEE;
+    bank.cumulative_borrow_index = WAD;
+    bank.cumulative_supply_index = WAD;
+    bank.last_updated = Clock::get()?.unix_timestamp;
+    bank.oracle = oracle;
+    bank.validate_config()?;
+
+    emit!(BankInitializedEvent {
+        bank: bank.key(),
+        mint: bank.mint_address,
+        liquidation_threshold,
+        max_ltv,
+        timestamp: bank.last_updated,
+    });
 
     Ok(())
 }
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    emit_protocol_config, program::LendingBorrowing, DappError, Protocol, ANCHOR_DISCRIMINATOR,
+    DEFAULT_CONFIG_DELAY, DEFAULT_FORBIDDEN_EXTENSIONS,
+};
+
+// solo la upgrade authority del programa puede crearlo y queda como admin,
+// evita que otro lo inicialice primero despues del deploy
+#[derive(Accounts)]
+pub struct InitializeProtocol<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ DappError::Unauthorized
+    )]
+    pub program: Program<'info, LendingBorrowing>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ DappError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Protocol::INIT_SPACE + ANCHOR_DISCRIMINATOR,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_initialize_protocol(
+    ctx: Context<InitializeProtocol>,
+    guardian: Pubkey,
+    fee_receiver: Pubkey,
+) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+
+    protocol.admin = ctx.accounts.admin.key();
+    protocol.guardian = guardian;
+    protocol.fee_receiver = fee_receiver;
+    protocol.config_delay = DEFAULT_CONFIG_DELAY;
+    protocol.forbidden_extensions = DEFAULT_FORBIDDEN_EXTENSIONS;
+
+    emit_protocol_config(protocol)
+}
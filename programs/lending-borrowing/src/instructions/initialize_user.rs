@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::{User, ANCHOR_DISCRIMINATOR};
+use crate::{User, UserInitializedEvent, ANCHOR_DISCRIMINATOR};
 
 #[derive(Accounts)]
 pub struct InitializeUser<'info> {
@@ -19,11 +19,16 @@ pub struct InitializeUser<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn process_initialize_user(ctx: Context<InitializeUser>, usdc_address: Pubkey) -> Result<()> {
+pub fn process_initialize_user(ctx: Context<InitializeUser>) -> Result<()> {
     let user: &mut Account<'_, User> = &mut ctx.accounts.user;
 
     user.owner = ctx.accounts.signer.key();
-    user.usdc_address = usdc_address;
+
+    emit!(UserInitializedEvent {
+        user: user.owner,
+        user_account: user.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -1,36 +1,45 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{self, Mint, TokenAccount, TokenInterface},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 use crate::{
-    Bank, DappError, User, ANCHOR_DISCRIMINATOR, MAX_AGE, SOL_USD_FEED_ID, USDC_USD_FEED_ID,
+    burn_receipts, compute_health, get_bank_price, mul_div, settle_position_rewards, token_value,
+    transfer_tokens, transfer_tokens_in, value_to_amount, Bank, DappError, LiquidationEvent,
+    Protocol, RewardPool, RewardSide, Rounding, User, Wad, BPS_DENOMINATOR, PAUSE_LIQUIDATE,
 };
 
-use super::calculate_accrued_interest;
-
+// remaining_accounts: [bank, price_update, fallback_feed] por cada posicion del borrower (ver health.rs)
 #[derive(Accounts)]
 pub struct LiquidatePosition<'info> {
     #[account(mut)]
     pub liquidator: Signer<'info>,
 
     #[account(
-        init_if_needed,
-        payer = liquidator,
-        space = User::INIT_SPACE + ANCHOR_DISCRIMINATOR,
-        seeds = [liquidator.key().as_ref()],
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_LIQUIDATE) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: wallet del usuario a liquidar, solo se usa para derivar su cuenta User
+    pub borrower: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [borrower.key().as_ref()],
         bump
     )]
-    pub user: Account<'info, User>,
+    pub borrower_user: Account<'info, User>,
 
     #[account(
         init_if_needed,
         payer = liquidator,
         associated_token::mint = collateral_mint,
         associated_token::authority = liquidator,
-        associated_token::token_program = token_program
+        associated_token::token_program = collateral_token_program
     )]
     pub collateral_liquidator_token_account: InterfaceAccount<'info, TokenAccount>,
 
@@ -39,21 +48,23 @@ pub struct LiquidatePosition<'info> {
         payer = liquidator,
         associated_token::mint = borrowed_mint,
         associated_token::authority = liquidator,
-        associated_token::token_program = token_program
+        associated_token::token_program = borrowed_token_program
     )]
     pub borrowed_liquidator_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         seeds = [collateral_mint.key().as_ref()],
-        bump
+        bump,
+        constraint = collateral_bank.status.allows_reduce() @ DappError::BankFrozen
     )]
     pub collateral_bank: Account<'info, Bank>,
 
     #[account(
         mut,
         seeds = [borrowed_mint.key().as_ref()],
-        bump
+        bump,
+        constraint = borrowed_bank.status.allows_reduce() @ DappError::BankFrozen
     )]
     pub borrowed_bank: Account<'info, Bank>,
 
@@ -71,119 +82,275 @@ pub struct LiquidatePosition<'info> {
     )]
     pub borrowed_bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"receipt".as_ref(), collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub collateral_receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral".as_ref(), collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // obligatorios si los banks tienen reward pool del lado que cambia
+    #[account(
+        mut,
+        address = collateral_bank.deposit_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub collateral_reward_pool: Option<Box<Account<'info, RewardPool>>>,
+
+    #[account(
+        mut,
+        address = borrowed_bank.borrow_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub borrowed_reward_pool: Option<Box<Account<'info, RewardPool>>>,
+
+    #[account(mint::token_program = collateral_token_program)]
     pub collateral_mint: InterfaceAccount<'info, Mint>,
+    // con el mismo mint ambos banks serian la misma cuenta cargada dos veces y al
+    // serializar una copia pisaria los cambios de la otra
+    #[account(
+        mint::token_program = borrowed_token_program,
+        constraint = borrowed_mint.key() != collateral_mint.key() @ DappError::SameLiquidationBank
+    )]
     pub borrowed_mint: InterfaceAccount<'info, Mint>,
 
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub collateral_price_update: Account<'info, PriceUpdateV2>,
+    pub borrowed_price_update: Account<'info, PriceUpdateV2>,
+
+    /// CHECK: validado contra bank.oracle.fallback en get_bank_price
+    pub collateral_fallback_feed: UncheckedAccount<'info>,
+    /// CHECK: validado contra bank.oracle.fallback en get_bank_price
+    pub borrowed_fallback_feed: UncheckedAccount<'info>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    // cada mint puede ser de un programa distinto (spl-token / token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub borrowed_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn process_liquidate_position(ctx: Context<LiquidatePosition>) -> Result<()> {
+pub fn process_liquidate_position<'info>(
+    ctx: Context<'_, '_, '_, 'info, LiquidatePosition<'info>>,
+    repay_amount: u64,
+    min_collateral_out: u64,
+) -> Result<()> {
+    let collateral_bank_key = ctx.accounts.collateral_bank.key();
+    let borrowed_bank_key = ctx.accounts.borrowed_bank.key();
     let collateral_bank = &mut ctx.accounts.collateral_bank;
     let borrowed_bank = &mut ctx.accounts.borrowed_bank;
-    let user = &mut ctx.accounts.user;
+    let user = &mut ctx.accounts.borrower_user;
     let clock = Clock::get()?;
 
-    let price_update = &mut ctx.accounts.price_update;
-
-    let sol_feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
-    let usdc_feed_id = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
-
-    let sol_price = price_update.get_price_no_older_than(&clock, MAX_AGE, &sol_feed_id)?;
-    let usdc_price = price_update.get_price_no_older_than(&clock, MAX_AGE, &usdc_feed_id)?;
-
-    let (total_collateral, total_borrowed): (u64, u64) =
-        if ctx.accounts.collateral_mint.key() == user.usdc_address {
-            let new_usdc = calculate_accrued_interest(
-                user.deposited_usdc,
-                collateral_bank.interest_rate,
-                user.last_updated,
-            )?;
-            let new_sol = calculate_accrued_interest(
-                user.borrowed_sol,
-                borrowed_bank.interest_rate,
-                user.last_updated_borrowed,
-            )?;
-
-            (
-                usdc_price.price as u64 * new_usdc,
-                sol_price.price as u64 * new_sol,
-            )
-        } else {
-            let new_sol = calculate_accrued_interest(
-                user.deposited_sol,
-                collateral_bank.interest_rate,
-                user.last_updated,
-            )?;
-            let new_usdc = calculate_accrued_interest(
-                user.borrowed_usdc,
-                borrowed_bank.interest_rate,
-                user.last_updated_borrowed,
-            )?;
-
-            (
-                sol_price.price as u64 * new_sol,
-                usdc_price.price as u64 * new_usdc,
-            )
-        };
-
-    let health_factor = (total_collateral as f64 * collateral_bank.liquidation_threshold as f64)
-        / total_borrowed as f64;
-
-    require!(health_factor < 1.0, DappError::LiquidationThresholdIsTooLow);
+    collateral_bank.accrue_interest(clock.unix_timestamp)?;
+    borrowed_bank.accrue_interest(clock.unix_timestamp)?;
+
+    let health = compute_health(
+        user,
+        ctx.remaining_accounts,
+        &[
+            (collateral_bank_key, collateral_bank),
+            (borrowed_bank_key, borrowed_bank),
+        ],
+        &clock,
+    )?;
+    require!(
+        health.is_liquidatable(),
+        DappError::LiquidationThresholdIsTooLow
+    );
+
+    // los rewards se acreditan con las shares previas a la liquidacion
+    let borrowed_position = user.position_mut(&borrowed_bank_key)?;
+    settle_position_rewards(
+        borrowed_bank,
+        RewardSide::Borrow,
+        ctx.accounts.borrowed_reward_pool.as_deref_mut(),
+        borrowed_position,
+        clock.unix_timestamp,
+    )?;
+    let borrow_shares = borrowed_position.borrow_shares;
+
+    let collateral_position = user.position_mut(&collateral_bank_key)?;
+    settle_position_rewards(
+        collateral_bank,
+        RewardSide::Deposit,
+        ctx.accounts.collateral_reward_pool.as_deref_mut(),
+        collateral_position,
+        clock.unix_timestamp,
+    )?;
+    let deposit_shares = collateral_position.deposit_shares;
+    let total_borrowed = borrowed_bank.borrow_shares_to_amount(borrow_shares)?;
+    let total_collateral = collateral_bank.deposit_shares_to_amount(deposit_shares)?;
+
+    // no se puede liquidar mas que close_factor * deuda en una unica liquidacion
+    let max_repay = mul_div(
+        total_borrowed,
+        borrowed_bank.liquidation_close_factor,
+        BPS_DENOMINATOR,
+        Rounding::Down,
+    )?;
+    let mut repay_amount = repay_amount.min(max_repay);
+    require!(repay_amount > 0, DappError::InvalidLiquidationAmount);
+
+    let collateral_price = get_bank_price(
+        collateral_bank,
+        &ctx.accounts.collateral_price_update,
+        &ctx.accounts.collateral_fallback_feed,
+        &clock,
+    )?;
+    let borrowed_price = get_bank_price(
+        borrowed_bank,
+        &ctx.accounts.borrowed_price_update,
+        &ctx.accounts.borrowed_fallback_feed,
+        &clock,
+    )?;
+
+    // colateral a entregar = valor repagado * (1 + bonus) / precio del colateral
+    // se usa el precio medio, el ajuste por conf solo aplica al chequeo de health
+    let bonus_multiplier =
+        Wad::ONE.checked_add(Wad::from_bps(collateral_bank.liquidation_bonus))?;
+    let seized_value = token_value(
+        repay_amount,
+        borrowed_bank.mint_decimals,
+        &borrowed_price,
+        Rounding::Down,
+    )?
+    .checked_mul(bonus_multiplier, Rounding::Down)?;
+    let mut collateral_amount = value_to_amount(
+        seized_value,
+        collateral_bank.mint_decimals,
+        &collateral_price,
+        Rounding::Down,
+    )?;
+
+    // si no alcanza el colateral se entrega todo y se reduce el repago en proporcion
+    if collateral_amount > total_collateral {
+        repay_amount = mul_div(
+            repay_amount,
+            total_collateral,
+            collateral_amount,
+            Rounding::Up,
+        )?;
+        collateral_amount = total_collateral;
+    }
+    require!(
+        collateral_amount >= min_collateral_out,
+        DappError::SlippageExceeded
+    );
 
     // transfer to bank
-    let liquidation_amount = total_borrowed
-        .checked_mul(borrowed_bank.liquidation_close_factor)
-        .unwrap();
-    token_interface::transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::TransferChecked {
-                from: ctx
-                    .accounts
-                    .borrowed_liquidator_token_account
-                    .to_account_info(),
-                mint: ctx.accounts.borrowed_mint.to_account_info(),
-                to: ctx.accounts.borrowed_bank_token_account.to_account_info(),
-                authority: ctx.accounts.liquidator.to_account_info(),
-            },
-        ),
-        liquidation_amount,
-        ctx.accounts.borrowed_mint.decimals,
+    // con transfer fee el colateral ya se calculo sobre el monto bruto y solo se
+    // descuenta de la deuda lo recibido, el fee lo absorbe el borrower
+    let repay_amount = transfer_tokens_in(
+        &ctx.accounts.borrowed_token_program,
+        ctx.accounts
+            .borrowed_liquidator_token_account
+            .to_account_info(),
+        &ctx.accounts.borrowed_mint,
+        &mut ctx.accounts.borrowed_bank_token_account,
+        ctx.accounts.liquidator.to_account_info(),
+        ctx.remaining_accounts,
+        repay_amount,
+        &[],
     )?;
 
+    // update borrowed bank and user debt
+    let repaid_shares = if repay_amount >= total_borrowed {
+        borrow_shares
+    } else {
+        borrowed_bank
+            .amount_to_borrow_shares(repay_amount, Rounding::Down)?
+            .min(borrow_shares)
+    };
+    borrowed_bank.total_borrowed = borrowed_bank.total_borrowed.saturating_sub(repay_amount);
+    borrowed_bank.total_borrow_shares = borrowed_bank
+        .total_borrow_shares
+        .saturating_sub(repaid_shares);
+    user.position_mut(&borrowed_bank_key)?.borrow_shares -= repaid_shares;
+
+    // update collateral bank and user collateral
+    let seized_shares = if collateral_amount == total_collateral {
+        deposit_shares
+    } else {
+        collateral_bank
+            .amount_to_deposit_shares(collateral_amount, Rounding::Up)?
+            .min(deposit_shares)
+    };
+    collateral_bank.total_deposited = collateral_bank
+        .total_deposited
+        .checked_sub(collateral_amount)
+        .ok_or(DappError::MathOverflow)?;
+    collateral_bank.total_deposit_shares = collateral_bank
+        .total_deposit_shares
+        .checked_sub(seized_shares)
+        .ok_or(DappError::MathOverflow)?;
+    user.position_mut(&collateral_bank_key)?.deposit_shares -= seized_shares;
+    user.remove_empty_positions();
+
     // transfer to liquidator
     let collateral_mint_key = ctx.accounts.collateral_mint.key();
+    let collateral_seeds: &[&[&[u8]]] = &[&[
+        b"collateral".as_ref(),
+        collateral_mint_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ]];
+    burn_receipts(
+        &ctx.accounts.collateral_token_program,
+        &ctx.accounts.collateral_receipt_mint,
+        &ctx.accounts.collateral_vault,
+        seized_shares,
+        collateral_seeds,
+    )?;
+
     let signer_seeds: &[&[&[u8]]] = &[&[
         b"treasury".as_ref(),
         collateral_mint_key.as_ref(),
         &[ctx.bumps.collateral_bank_token_account],
     ]];
 
-    let liquidator_amount =
-        (liquidation_amount * collateral_bank.liquidation_bonus) + liquidation_amount;
-
-    token_interface::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::TransferChecked {
-                from: ctx.accounts.collateral_bank_token_account.to_account_info(),
-                mint: ctx.accounts.collateral_mint.to_account_info(),
-                to: ctx
-                    .accounts
-                    .collateral_liquidator_token_account
-                    .to_account_info(),
-                authority: ctx.accounts.collateral_bank_token_account.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        liquidator_amount,
-        ctx.accounts.collateral_mint.decimals,
+    transfer_tokens(
+        &ctx.accounts.collateral_token_program,
+        ctx.accounts.collateral_bank_token_account.to_account_info(),
+        &ctx.accounts.collateral_mint,
+        ctx.accounts
+            .collateral_liquidator_token_account
+            .to_account_info(),
+        ctx.accounts.collateral_bank_token_account.to_account_info(),
+        ctx.remaining_accounts,
+        collateral_amount,
+        signer_seeds,
     )?;
 
+    // liquidez con la que se calcula la utilizacion del proximo periodo
+    ctx.accounts.collateral_bank_token_account.reload()?;
+    ctx.accounts
+        .collateral_bank
+        .sync_liquidity(ctx.accounts.collateral_bank_token_account.amount);
+    ctx.accounts
+        .borrowed_bank
+        .sync_liquidity(ctx.accounts.borrowed_bank_token_account.amount);
+
+    emit!(LiquidationEvent {
+        liquidator: ctx.accounts.liquidator.key(),
+        borrower: ctx.accounts.borrower.key(),
+        collateral_bank: collateral_bank_key,
+        borrowed_bank: borrowed_bank_key,
+        repay_amount,
+        repaid_shares,
+        collateral_amount,
+        seized_shares,
+        collateral_price: collateral_price.price,
+        collateral_price_exponent: collateral_price.exponent,
+        borrowed_price: borrowed_price.price,
+        borrowed_price_exponent: borrowed_price.exponent,
+        cumulative_borrow_index: ctx.accounts.borrowed_bank.cumulative_borrow_index,
+        cumulative_supply_index: ctx.accounts.collateral_bank.cumulative_supply_index,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    settle_position_rewards, transfer_tokens, Bank, CollateralLockedEvent, DappError, Protocol,
+    RewardPool, RewardSide, User, PAUSE_DEPOSIT,
+};
+
+// bloquea receipts del usuario como colateral: pasan al vault y se acreditan como
+// deposit_shares de su posicion. aumenta el colateral, mismos chequeos que deposit
+#[derive(Accounts)]
+pub struct LockCollateral<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_DEPOSIT) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump,
+        constraint = bank.status.allows_increase() @ DappError::BankNotActive
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        seeds = [b"receipt".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = signer,
+        token::token_program = token_program
+    )]
+    pub user_receipt_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // obligatorio si el bank tiene reward pool de depositos
+    #[account(
+        mut,
+        address = bank.deposit_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn process_lock_collateral(ctx: Context<LockCollateral>, shares: u64) -> Result<()> {
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.user_receipt_account.to_account_info(),
+        &ctx.accounts.receipt_mint,
+        ctx.accounts.collateral_vault.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        &[],
+        shares,
+        &[],
+    )?;
+
+    let bank_key = ctx.accounts.bank.key();
+    let bank = &mut ctx.accounts.bank;
+    let user = &mut ctx.accounts.user;
+    let now = Clock::get()?.unix_timestamp;
+
+    bank.accrue_interest(now)?;
+
+    let position = user.get_or_create_position(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Deposit,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        now,
+    )?;
+    position.deposit_shares = position
+        .deposit_shares
+        .checked_add(shares)
+        .ok_or(DappError::MathOverflow)?;
+
+    emit!(CollateralLockedEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        shares,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -1,17 +1,59 @@
+pub mod accept_admin;
 pub mod borrow_token;
+pub mod cancel_config_change;
+pub mod claim_rewards;
+pub mod collect_fees;
+pub mod create_reward_pool;
 pub mod deposit_token;
+pub mod execute_config_change;
+pub mod flash_borrow;
+pub mod flash_repay;
 pub mod initialize_bank;
+pub mod initialize_protocol;
 pub mod initialize_user;
 pub mod liquidate_position;
+pub mod lock_collateral;
+pub mod queue_config_change;
+pub mod refresh_bank;
 pub mod repay_token;
+pub mod request_unstake_insurance;
+pub mod set_bank_status;
+pub mod set_paused;
+pub mod settle_bad_debt;
 pub mod shared;
+pub mod stake_insurance;
+pub mod transfer_admin;
+pub mod unlock_collateral;
+pub mod unstake_insurance;
+pub mod update_protocol_config;
 pub mod withdraw_token;
 
+pub use accept_admin::*;
 pub use borrow_token::*;
+pub use cancel_config_change::*;
+pub use claim_rewards::*;
+pub use collect_fees::*;
+pub use create_reward_pool::*;
 pub use deposit_token::*;
+pub use execute_config_change::*;
+pub use flash_borrow::*;
+pub use flash_repay::*;
 pub use initialize_bank::*;
+pub use initialize_protocol::*;
 pub use initialize_user::*;
 pub use liquidate_position::*;
+pub use lock_collateral::*;
+pub use queue_config_change::*;
+pub use refresh_bank::*;
 pub use repay_token::*;
+pub use request_unstake_insurance::*;
+pub use set_bank_status::*;
+pub use set_paused::*;
+pub use settle_bad_debt::*;
 pub use shared::*;
+pub use stake_insurance::*;
+pub use transfer_admin::*;
+pub use unlock_collateral::*;
+pub use unstake_insurance::*;
+pub use update_protocol_config::*;
 pub use withdraw_token::*;
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    Bank, BankConfigParams, ConfigChangeQueuedEvent, DappError, PendingConfigChange, Protocol,
+    ANCHOR_DISCRIMINATOR,
+};
+
+// reemplaza al update_bank_config directo y a los setters de oracle, tasas, limites e
+// insurance: la config del bank solo cambia via queue_config_change + execute_config_change
+// despues de protocol.config_delay.
+// un unico cambio pendiente por bank, hay que ejecutarlo o cancelarlo antes de encolar otro
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        seeds = [bank.mint_address.as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PendingConfigChange::INIT_SPACE + ANCHOR_DISCRIMINATOR,
+        seeds = [b"pending_config".as_ref(), bank.key().as_ref()],
+        bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_queue_config_change(
+    ctx: Context<QueueConfigChange>,
+    params: BankConfigParams,
+) -> Result<()> {
+    // se valida contra la config actual para rechazar cambios invalidos de entrada
+    let mut bank = (*ctx.accounts.bank).clone();
+    bank.apply_config(&params);
+    bank.validate_config()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let executable_at = now
+        .checked_add(ctx.accounts.protocol.config_delay)
+        .ok_or(DappError::MathOverflow)?;
+
+    let pending = &mut ctx.accounts.pending_config_change;
+    pending.bank = ctx.accounts.bank.key();
+    pending.params = params;
+    pending.queued_at = now;
+    pending.executable_at = executable_at;
+
+    emit!(ConfigChangeQueuedEvent {
+        bank: pending.bank,
+        params,
+        executable_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
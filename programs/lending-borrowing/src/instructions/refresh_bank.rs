@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::{emit_rate_update, Bank};
+
+// permissionless, cualquiera puede actualizar los indices de un bank
+#[derive(Accounts)]
+pub struct RefreshBank<'info> {
+    #[account(
+        mut,
+        seeds = [bank.mint_address.as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+}
+
+pub fn process_refresh_bank(ctx: Context<RefreshBank>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let bank = &mut ctx.accounts.bank;
+    bank.accrue_interest(now)?;
+
+    emit_rate_update(bank.key(), bank, now)
+}
@@ -1,16 +1,26 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{self, Mint, TokenAccount, TokenInterface},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::{calculate_accrued_interest, Bank, DappError, User};
+use crate::{
+    settle_position_rewards, transfer_tokens_in, Bank, DappError, Protocol, RepayEvent, RewardPool,
+    RewardSide, Rounding, User, PAUSE_REPAY,
+};
 
 #[derive(Accounts)]
 pub struct RepayToken<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_REPAY) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
     #[account(
         mut,
         seeds = [signer.key().as_ref()],
@@ -29,7 +39,8 @@ pub struct RepayToken<'info> {
     #[account(
         mut,
         seeds = [mint_account.key().as_ref()],
-        bump
+        bump,
+        constraint = bank.status.allows_reduce() @ DappError::BankFrozen
     )]
     pub bank: Account<'info, Bank>,
 
@@ -40,80 +51,82 @@ pub struct RepayToken<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    // obligatorio si el bank tiene reward pool de prestamos
+    #[account(
+        mut,
+        address = bank.borrow_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
     pub mint_account: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn process_repay_token(ctx: Context<RepayToken>, amount: u64) -> Result<()> {
+pub fn process_repay_token<'info>(
+    ctx: Context<'_, '_, '_, 'info, RepayToken<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
     let user = &mut ctx.accounts.user;
     let bank = &mut ctx.accounts.bank;
 
     let now = Clock::get()?.unix_timestamp;
-
-    let (user_borrowed, user_borrowed_shares) =
-        if ctx.accounts.mint_account.key() == user.usdc_address {
-            (user.borrowed_usdc, user.borrowed_usdc_shares)
-        } else {
-            (user.borrowed_sol, user.borrowed_sol_shares)
-        };
-
-    let accrued_user_debt = calculate_accrued_interest(
-        user_borrowed,
-        bank.interest_rate,
-        user.last_updated_borrowed,
-    )?;
-    require!(amount <= accrued_user_debt, DappError::OverRepayableAmount);
-
-    let accrued_total_borrowed = calculate_accrued_interest(
-        bank.total_borrowed,
-        bank.interest_rate,
-        bank.last_updated_borrowed,
+    bank.accrue_interest(now)?;
+
+    let position = user.position_mut(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Borrow,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        now,
     )?;
 
-    let repay_share_fraction = (amount as u128)
-        .checked_mul(bank.total_borrow_shares as u128)
-        .unwrap()
-        .checked_div(accrued_total_borrowed as u128)
-        .unwrap() as u64;
+    let accrued_user_debt = bank.borrow_shares_to_amount(position.borrow_shares)?;
+    require!(amount <= accrued_user_debt, DappError::OverRepayableAmount);
 
     // transfer token: user_token_account -> bank_token_account
-    token_interface::transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::TransferChecked {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                mint: ctx.accounts.mint_account.to_account_info(),
-                to: ctx.accounts.bank_token_account.to_account_info(),
-                authority: ctx.accounts.signer.to_account_info(),
-            },
-        ),
+    // con transfer fee solo se descuenta de la deuda lo recibido
+    let amount = transfer_tokens_in(
+        &ctx.accounts.token_program,
+        ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.bank_token_account,
+        ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
         amount,
-        ctx.accounts.mint_account.decimals,
+        &[],
     )?;
 
+    // shares a quemar -> redondeo hacia abajo, la deuda restante nunca se subestima
+    let repay_share_fraction = if amount == accrued_user_debt {
+        position.borrow_shares
+    } else {
+        bank.amount_to_borrow_shares(amount, Rounding::Down)?
+    };
+
     // update bank and user accounts
-    bank.total_borrowed = accrued_total_borrowed.checked_sub(amount).unwrap_or(0);
+    bank.total_borrowed = bank.total_borrowed.saturating_sub(amount);
     bank.total_borrow_shares = bank
         .total_borrow_shares
-        .checked_sub(repay_share_fraction)
-        .unwrap_or(0);
-    bank.last_updated_borrowed = now;
-
-    if ctx.accounts.mint_account.key() == user.usdc_address {
-        user.borrowed_usdc = accrued_user_debt.checked_sub(amount).unwrap_or(0);
-        user.borrowed_usdc_shares = user_borrowed_shares
-            .checked_sub(repay_share_fraction)
-            .unwrap_or(0);
-    } else {
-        user.borrowed_sol = accrued_user_debt.checked_sub(amount).unwrap_or(0);
-        user.borrowed_sol_shares = user_borrowed_shares
-            .checked_sub(repay_share_fraction)
-            .unwrap_or(0);
-    }
+        .saturating_sub(repay_share_fraction);
+
+    position.borrow_shares = position.borrow_shares.saturating_sub(repay_share_fraction);
+    user.remove_empty_positions();
 
-    user.last_updated_borrowed = now;
+    // liquidez con la que se calcula la utilizacion del proximo periodo
+    bank.sync_liquidity(ctx.accounts.bank_token_account.amount);
+
+    emit!(RepayEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        amount,
+        shares: repay_share_fraction,
+        cumulative_borrow_index: bank.cumulative_borrow_index,
+        timestamp: now,
+    });
 
     Ok(())
 }
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{Bank, DappError, InsuranceStake, InsuranceUnstakeRequestEvent};
+
+#[derive(Accounts)]
+pub struct RequestUnstakeInsurance<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = bank,
+        seeds = [b"insurance_stake".as_ref(), bank.key().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub insurance_stake: Account<'info, InsuranceStake>,
+
+    #[account(
+        seeds = [bank.mint_address.as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+}
+
+// las shares siguen en el fondo (y expuestas al slashing) hasta que termine el cooldown
+pub fn process_request_unstake_insurance(
+    ctx: Context<RequestUnstakeInsurance>,
+    shares: u64,
+) -> Result<()> {
+    let insurance_stake = &mut ctx.accounts.insurance_stake;
+    insurance_stake.sync_epoch(ctx.accounts.bank.insurance_epoch);
+
+    require!(
+        shares > 0 && shares <= insurance_stake.shares,
+        DappError::InsufficientInsuranceShares
+    );
+
+    insurance_stake.pending_unstake_shares = shares;
+    insurance_stake.unstake_requested_at = Clock::get()?.unix_timestamp;
+
+    emit!(InsuranceUnstakeRequestEvent {
+        user: insurance_stake.owner,
+        bank: insurance_stake.bank,
+        shares,
+        timestamp: insurance_stake.unstake_requested_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{Bank, BankStatus, BankStatusUpdatedEvent, DappError, Protocol};
+
+// permite cerrar un unico bank sin pausar todo el protocolo
+#[derive(Accounts)]
+pub struct SetBankStatus<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        has_one = guardian @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [bank.mint_address.as_ref()],
+        bump
+    )]
+    pub bank: Account<'info, Bank>,
+}
+
+pub fn process_set_bank_status(ctx: Context<SetBankStatus>, status: BankStatus) -> Result<()> {
+    let bank = &mut ctx.accounts.bank;
+
+    // el interes hasta ahora se capitaliza antes del cambio
+    let now = Clock::get()?.unix_timestamp;
+    bank.accrue_interest(now)?;
+
+    bank.status = status;
+
+    emit!(BankStatusUpdatedEvent {
+        bank: bank.key(),
+        status,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{DappError, PauseUpdatedEvent, Protocol, PAUSE_ALL};
+
+// el admin puede pausar y despausar, el guardian solo puede agregar pausas
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn process_set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    require!(paused & !PAUSE_ALL == 0, DappError::InvalidPauseFlags);
+
+    let signer = ctx.accounts.signer.key();
+    let protocol = &mut ctx.accounts.protocol;
+
+    let is_admin = signer == protocol.admin;
+    let only_adds_pauses = paused & protocol.paused == protocol.paused;
+    require!(
+        is_admin || (signer == protocol.guardian && only_adds_pauses),
+        DappError::Unauthorized
+    );
+
+    protocol.paused = paused;
+
+    emit!(PauseUpdatedEvent {
+        signer,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    compute_health, settle_position_rewards, sweep_insurance_fees, transfer_tokens_in,
+    BadDebtEvent, Bank, DappError, Protocol, RewardPool, RewardSide, User, Wad,
+    MAX_BAD_DEBT_COLLATERAL_VALUE, PAUSE_LIQUIDATE,
+};
+
+// permissionless, solo aplica cuando el colateral del usuario vale menos que MAX_BAD_DEBT_COLLATERAL_VALUE.
+// remaining_accounts: [bank, price_update, fallback_feed] por cada posicion del borrower (ver health.rs)
+#[derive(Accounts)]
+pub struct SettleBadDebt<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_LIQUIDATE) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: wallet del usuario con deuda incobrable, solo se usa para derivar su cuenta User
+    pub borrower: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [borrower.key().as_ref()],
+        bump
+    )]
+    pub borrower_user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump,
+        constraint = bank.status.allows_reduce() @ DappError::BankFrozen
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+
+    // obligatorio si el bank tiene reward pool de prestamos
+    #[account(
+        mut,
+        address = bank.borrow_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn process_settle_bad_debt<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleBadDebt<'info>>,
+) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
+    let user = &mut ctx.accounts.borrower_user;
+    let bank = &mut ctx.accounts.bank;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    bank.accrue_interest(now)?;
+
+    // el colateral que le quede tiene que ser polvo, que nadie liquidaria. si el polvo esta
+    // en el mismo bank que la deuda tampoco se puede liquidar
+    let health = compute_health(user, ctx.remaining_accounts, &[(bank_key, bank)], &clock)?;
+    require!(
+        health.liquidation_collateral_value <= Wad(MAX_BAD_DEBT_COLLATERAL_VALUE),
+        DappError::BorrowerHasCollateral
+    );
+
+    let position = user.position_mut(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Borrow,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        now,
+    )?;
+    let borrow_shares = position.borrow_shares;
+    let bad_debt = bank.borrow_shares_to_amount(borrow_shares)?;
+    require!(bad_debt > 0, DappError::NoBadDebt);
+
+    // primero se cubre con el insurance fund, devolviendo los tokens al treasury
+    sweep_insurance_fees(
+        bank,
+        &ctx.accounts.bank_token_account,
+        &mut ctx.accounts.insurance_vault,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        ctx.bumps.bank_token_account,
+    )?;
+    let mut covered_by_insurance = bad_debt.min(ctx.accounts.insurance_vault.amount);
+
+    if covered_by_insurance > 0 {
+        let mint_key = ctx.accounts.mint_account.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"insurance".as_ref(),
+            mint_key.as_ref(),
+            &[ctx.bumps.insurance_vault],
+        ]];
+
+        // con transfer fee solo cubre lo que llega al treasury
+        covered_by_insurance = transfer_tokens_in(
+            &ctx.accounts.token_program,
+            ctx.accounts.insurance_vault.to_account_info(),
+            &ctx.accounts.mint_account,
+            &mut ctx.accounts.bank_token_account,
+            ctx.accounts.insurance_vault.to_account_info(),
+            ctx.remaining_accounts,
+            covered_by_insurance,
+            signer_seeds,
+        )?;
+    }
+
+    // despues con las reservas del protocolo
+    let covered_by_protocol = (bad_debt - covered_by_insurance).min(bank.accumulated_protocol_fees);
+    bank.accumulated_protocol_fees -= covered_by_protocol;
+
+    // el resto lo absorben los depositantes
+    let socialized_loss =
+        bank.socialize_loss(bad_debt - covered_by_insurance - covered_by_protocol)?;
+
+    // lo que no se pudo socializar (sin depositantes o con perdida mayor a lo depositado)
+    // queda registrado como deficit del bank
+    let unrecovered = bad_debt - covered_by_insurance - covered_by_protocol - socialized_loss;
+    bank.unrecovered_bad_debt = bank
+        .unrecovered_bad_debt
+        .checked_add(unrecovered)
+        .ok_or(DappError::MathOverflow)?;
+
+    bank.total_borrowed = bank.total_borrowed.saturating_sub(bad_debt);
+    bank.total_borrow_shares = bank.total_borrow_shares.saturating_sub(borrow_shares);
+
+    position.borrow_shares = 0;
+    user.remove_empty_positions();
+
+    // el sweep saco fees del treasury y el insurance devolvio tokens
+    ctx.accounts.bank_token_account.reload()?;
+    let bank = &mut ctx.accounts.bank;
+    bank.sync_liquidity(ctx.accounts.bank_token_account.amount);
+
+    emit!(BadDebtEvent {
+        user: ctx.accounts.borrower.key(),
+        bank: bank_key,
+        bad_debt,
+        covered_by_insurance,
+        covered_by_protocol,
+        socialized_loss,
+        unrecovered,
+        unrecovered_bad_debt: bank.unrecovered_bad_debt,
+        cumulative_supply_index: bank.cumulative_supply_index,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,246 @@
-use std::f64::consts::E;
-
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{BaseStateWithExtensions, StateWithExtensions},
+        onchain::invoke_transfer_checked,
+    },
+    token_interface::{burn, mint_to, Burn, Mint, MintTo, Token2022, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    Bank, BankConfigUpdatedEvent, DappError, Position, Protocol, ProtocolConfigUpdatedEvent,
+    RateUpdateEvent, RewardPool, RewardSide,
+};
 
-pub fn calculate_accrued_interest(
-    deposited: u64,
-    interest_rate: u64,
-    last_updated: i64,
+/// `transfer_checked` that also works for Token-2022 mints with a transfer
+/// hook: the extra accounts the hook needs are looked up by key in
+/// `extra_accounts` (the instruction remaining accounts). Accounts that are not
+/// needed by the hook, such as the health accounts, are ignored.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint.to_account_info(),
+        to,
+        authority,
+        extra_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// transfiere hacia una cuenta del programa y devuelve lo que efectivamente llego,
+// que con un mint con transfer fee es menos que `amount`
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_tokens_in<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<u64> {
-    let now = Clock::get()?.unix_timestamp;
-    let time_diff = now - last_updated;
-    let new_value = (deposited as f64 * E.powf(interest_rate as f64 * time_diff as f64)) as u64;
-    Ok(new_value)
+    let balance_before = to.amount;
+
+    transfer_tokens(
+        token_program,
+        from,
+        mint,
+        to.to_account_info(),
+        authority,
+        extra_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    to.reload()?;
+    to.amount
+        .checked_sub(balance_before)
+        .ok_or(error!(DappError::MathOverflow))
+}
+
+// rechaza mints de token-2022 con alguna de las extensiones marcadas en `forbidden`
+pub fn check_mint_extensions(mint: &InterfaceAccount<Mint>, forbidden: u64) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != Token2022::id() {
+        return Ok(());
+    }
+
+    let data = info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in mint.get_extension_types()? {
+        let bit = u16::from(extension);
+        require!(
+            bit >= u64::BITS as u16 || forbidden & (1 << bit) == 0,
+            DappError::ForbiddenMintExtension
+        );
+    }
+
+    Ok(())
+}
+
+// receipts del bank: 1 token = 1 deposit share, el bank PDA es la mint authority
+pub fn mint_receipts<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    bank: AccountInfo<'info>,
+    shares: u64,
+    bank_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: receipt_mint.to_account_info(),
+                to,
+                authority: bank,
+            },
+            bank_seeds,
+        ),
+        shares,
+    )
+}
+
+// quema receipts del vault de colateral, que es su propia authority
+pub fn burn_receipts<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    collateral_vault: &InterfaceAccount<'info, TokenAccount>,
+    shares: u64,
+    vault_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Burn {
+                mint: receipt_mint.to_account_info(),
+                from: collateral_vault.to_account_info(),
+                authority: collateral_vault.to_account_info(),
+            },
+            vault_seeds,
+        ),
+        shares,
+    )
+}
+
+// acredita los rewards pendientes de la posicion antes de cambiar sus shares del lado `side`.
+// si el bank tiene un reward pool para ese lado la instruccion lo tiene que recibir
+pub fn settle_position_rewards(
+    bank: &Bank,
+    side: RewardSide,
+    reward_pool: Option<&mut Account<RewardPool>>,
+    position: &mut Position,
+    now: i64,
+) -> Result<()> {
+    let expected = bank.reward_pool(side);
+    if expected == Pubkey::default() {
+        return Ok(());
+    }
+
+    let reward_pool = reward_pool.ok_or(DappError::InvalidRewardPool)?;
+    require_keys_eq!(reward_pool.key(), expected, DappError::InvalidRewardPool);
+
+    reward_pool.update(now, bank.total_shares(side))?;
+    position.settle_rewards(side, reward_pool.acc_reward_per_share)
+}
+
+// mueve los fees del insurance fund acumulados en el treasury al vault del insurance fund.
+// el vault queda recargado, su amount ya incluye lo movido
+pub fn sweep_insurance_fees<'info>(
+    bank: &mut Bank,
+    bank_token_account: &InterfaceAccount<'info, TokenAccount>,
+    insurance_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    mint_account: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    extra_accounts: &[AccountInfo<'info>],
+    treasury_bump: u8,
+) -> Result<()> {
+    // solo se puede mover lo que hay disponible en el treasury
+    let amount = bank
+        .accumulated_insurance_fees
+        .min(bank_token_account.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+
+    bank.accumulated_insurance_fees -= amount;
+
+    let mint_key = mint_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"treasury".as_ref(), mint_key.as_ref(), &[treasury_bump]]];
+
+    transfer_tokens_in(
+        token_program,
+        bank_token_account.to_account_info(),
+        mint_account,
+        insurance_vault,
+        bank_token_account.to_account_info(),
+        extra_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+// tasas e indices actuales del bank
+pub fn emit_rate_update(bank_key: Pubkey, bank: &Bank, now: i64) -> Result<()> {
+    let (utilization, borrow_rate, supply_rate) = bank.current_rates()?;
+
+    emit!(RateUpdateEvent {
+        bank: bank_key,
+        utilization: utilization.0,
+        borrow_rate: borrow_rate.0,
+        supply_rate: supply_rate.0,
+        cumulative_borrow_index: bank.cumulative_borrow_index,
+        cumulative_supply_index: bank.cumulative_supply_index,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// estado actual de la configuracion global
+pub fn emit_protocol_config(protocol: &Protocol) -> Result<()> {
+    emit!(ProtocolConfigUpdatedEvent {
+        admin: protocol.admin,
+        pending_admin: protocol.pending_admin,
+        guardian: protocol.guardian,
+        fee_receiver: protocol.fee_receiver,
+        config_delay: protocol.config_delay,
+        forbidden_extensions: protocol.forbidden_extensions,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// parametros de riesgo actuales del bank
+pub fn emit_bank_config(bank_key: Pubkey, bank: &Bank) -> Result<()> {
+    emit!(BankConfigUpdatedEvent {
+        bank: bank_key,
+        max_ltv: bank.max_ltv,
+        liquidation_threshold: bank.liquidation_threshold,
+        liquidation_bonus: bank.liquidation_bonus,
+        liquidation_close_factor: bank.liquidation_close_factor,
+        flash_loan_fee: bank.flash_loan_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
 }
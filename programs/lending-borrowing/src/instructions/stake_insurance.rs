@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    mul_div, sweep_insurance_fees, transfer_tokens_in, Bank, DappError, InsuranceStake,
+    InsuranceStakeEvent, Protocol, Rounding, ANCHOR_DISCRIMINATOR, PAUSE_DEPOSIT,
+};
+
+#[derive(Accounts)]
+pub struct StakeInsurance<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // stakear es un deposito en el fondo, respeta la pausa de depositos
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_DEPOSIT) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = InsuranceStake::INIT_SPACE + ANCHOR_DISCRIMINATOR,
+        seeds = [b"insurance_stake".as_ref(), bank.key().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub insurance_stake: Account<'info, InsuranceStake>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_account,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump,
+        constraint = bank.status.allows_increase() @ DappError::BankNotActive
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_stake_insurance<'info>(
+    ctx: Context<'_, '_, '_, 'info, StakeInsurance<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let bank = &mut ctx.accounts.bank;
+
+    let now = Clock::get()?.unix_timestamp;
+    bank.accrue_interest(now)?;
+
+    sweep_insurance_fees(
+        bank,
+        &ctx.accounts.bank_token_account,
+        &mut ctx.accounts.insurance_vault,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        ctx.bumps.bank_token_account,
+    )?;
+    bank.reset_depleted_insurance(ctx.accounts.insurance_vault.amount);
+
+    // sin stakers lo que hay en el fondo no es de nadie: vuelve al protocolo para que
+    // el primer staker no se lo quede
+    let unowned = ctx.accounts.insurance_vault.amount;
+    if bank.total_insurance_shares == 0 && unowned > 0 {
+        let mint_key = ctx.accounts.mint_account.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"insurance".as_ref(),
+            mint_key.as_ref(),
+            &[ctx.bumps.insurance_vault],
+        ]];
+
+        let returned = transfer_tokens_in(
+            &ctx.accounts.token_program,
+            ctx.accounts.insurance_vault.to_account_info(),
+            &ctx.accounts.mint_account,
+            &mut ctx.accounts.bank_token_account,
+            ctx.accounts.insurance_vault.to_account_info(),
+            ctx.remaining_accounts,
+            unowned,
+            signer_seeds,
+        )?;
+        ctx.accounts.insurance_vault.reload()?;
+
+        bank.accumulated_protocol_fees = bank
+            .accumulated_protocol_fees
+            .checked_add(returned)
+            .ok_or(DappError::MathOverflow)?;
+    }
+    let fund_value = ctx.accounts.insurance_vault.amount;
+
+    // con transfer fee las shares se calculan sobre lo recibido
+    let amount = transfer_tokens_in(
+        &ctx.accounts.token_program,
+        ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        &mut ctx.accounts.insurance_vault,
+        ctx.accounts.signer.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        &[],
+    )?;
+
+    // shares proporcionales al valor del fondo, 1:1 si es el primero
+    let shares = if bank.total_insurance_shares == 0 {
+        amount
+    } else {
+        mul_div(
+            amount,
+            bank.total_insurance_shares,
+            fund_value,
+            Rounding::Down,
+        )?
+    };
+
+    bank.total_insurance_shares = bank
+        .total_insurance_shares
+        .checked_add(shares)
+        .ok_or(DappError::MathOverflow)?;
+
+    let insurance_stake = &mut ctx.accounts.insurance_stake;
+    insurance_stake.sync_epoch(bank.insurance_epoch);
+    insurance_stake.owner = ctx.accounts.signer.key();
+    insurance_stake.bank = bank.key();
+    insurance_stake.shares = insurance_stake
+        .shares
+        .checked_add(shares)
+        .ok_or(DappError::MathOverflow)?;
+
+    emit!(InsuranceStakeEvent {
+        user: insurance_stake.owner,
+        bank: insurance_stake.bank,
+        amount,
+        shares,
+        timestamp: now,
+    });
+
+    Ok(())
+}
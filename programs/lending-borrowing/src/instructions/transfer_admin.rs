@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{emit_protocol_config, DappError, Protocol};
+
+// primer paso: el admin propone al nuevo admin, que tiene que aceptar con accept_admin
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn process_transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+
+    // Pubkey::default() cancela una transferencia pendiente
+    protocol.pending_admin = new_admin;
+
+    emit_protocol_config(protocol)
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    compute_health, settle_position_rewards, transfer_tokens, Bank, CollateralUnlockedEvent,
+    DappError, Protocol, RewardPool, RewardSide, User, PAUSE_WITHDRAW,
+};
+
+// saca deposit_shares de la posicion y las entrega como receipts transferibles.
+// remaining_accounts: [bank, price_update, fallback_feed] por cada posicion del usuario si tiene deuda
+#[derive(Accounts)]
+pub struct UnlockCollateral<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_WITHDRAW) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [signer.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump,
+        constraint = bank.status.allows_reduce() @ DappError::BankFrozen
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        seeds = [b"receipt".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_receipt_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // obligatorio si el bank tiene reward pool de depositos
+    #[account(
+        mut,
+        address = bank.deposit_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_unlock_collateral(ctx: Context<UnlockCollateral>, shares: u64) -> Result<()> {
+    let bank_key = ctx.accounts.bank.key();
+    let user = &mut ctx.accounts.user;
+    let bank = &mut ctx.accounts.bank;
+
+    let clock = Clock::get()?;
+    bank.accrue_interest(clock.unix_timestamp)?;
+
+    let position = user.position_mut(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Deposit,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        clock.unix_timestamp,
+    )?;
+    position.deposit_shares = position
+        .deposit_shares
+        .checked_sub(shares)
+        .ok_or(DappError::InsufficientBalance)?;
+    user.remove_empty_positions();
+
+    // igual que withdraw, el colateral restante tiene que seguir cubriendo la deuda
+    if user.has_debt() {
+        let health = compute_health(user, ctx.remaining_accounts, &[(bank_key, bank)], &clock)?;
+        require!(health.is_healthy(), DappError::UnhealthyPosition);
+    }
+
+    let mint_key = ctx.accounts.mint_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"collateral".as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ]];
+
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.collateral_vault.to_account_info(),
+        &ctx.accounts.receipt_mint,
+        ctx.accounts.user_receipt_account.to_account_info(),
+        ctx.accounts.collateral_vault.to_account_info(),
+        &[],
+        shares,
+        signer_seeds,
+    )?;
+
+    emit!(CollateralUnlockedEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        shares,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    mul_div, sweep_insurance_fees, transfer_tokens, Bank, DappError, InsuranceStake,
+    InsuranceUnstakeEvent, Protocol, Rounding, PAUSE_WITHDRAW,
+};
+
+#[derive(Accounts)]
+pub struct UnstakeInsurance<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_WITHDRAW) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        has_one = bank,
+        seeds = [b"insurance_stake".as_ref(), bank.key().as_ref(), signer.key().as_ref()],
+        bump
+    )]
+    pub insurance_stake: Account<'info, InsuranceStake>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint_account,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [mint_account.key().as_ref()],
+        bump,
+        constraint = bank.status.allows_reduce() @ DappError::BankFrozen
+    )]
+    pub bank: Account<'info, Bank>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_account: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process_unstake_insurance<'info>(
+    ctx: Context<'_, '_, '_, 'info, UnstakeInsurance<'info>>,
+) -> Result<()> {
+    let bank = &mut ctx.accounts.bank;
+    let insurance_stake = &mut ctx.accounts.insurance_stake;
+
+    let now = Clock::get()?.unix_timestamp;
+    insurance_stake.sync_epoch(bank.insurance_epoch);
+    let shares = insurance_stake.pending_unstake_shares;
+    require!(shares > 0, DappError::NoPendingUnstake);
+    insurance_stake.check_unstake_window(
+        now,
+        bank.insurance_cooldown,
+        bank.insurance_unstake_window,
+    )?;
+
+    bank.accrue_interest(now)?;
+
+    sweep_insurance_fees(
+        bank,
+        &ctx.accounts.bank_token_account,
+        &mut ctx.accounts.insurance_vault,
+        &ctx.accounts.mint_account,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        ctx.bumps.bank_token_account,
+    )?;
+    let fund_value = ctx.accounts.insurance_vault.amount;
+
+    // el valor de las shares ya refleja cualquier slashing durante el cooldown
+    let amount = mul_div(
+        shares,
+        fund_value,
+        bank.total_insurance_shares,
+        Rounding::Down,
+    )?;
+
+    insurance_stake.shares -= shares;
+    insurance_stake.pending_unstake_shares = 0;
+    insurance_stake.unstake_requested_at = 0;
+    bank.total_insurance_shares -= shares;
+
+    let mint_key = ctx.accounts.mint_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"insurance".as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.insurance_vault],
+    ]];
+
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.insurance_vault.to_account_info(),
+        &ctx.accounts.mint_account,
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.insurance_vault.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    emit!(InsuranceUnstakeEvent {
+        user: ctx.accounts.insurance_stake.owner,
+        bank: ctx.accounts.insurance_stake.bank,
+        amount,
+        shares,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{emit_protocol_config, DappError, Protocol, MIN_CONFIG_DELAY};
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ DappError::Unauthorized,
+        seeds = [b"protocol".as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn process_update_protocol_config(
+    ctx: Context<UpdateProtocolConfig>,
+    guardian: Pubkey,
+    fee_receiver: Pubkey,
+    config_delay: i64,
+    forbidden_extensions: u64,
+) -> Result<()> {
+    require!(
+        config_delay >= MIN_CONFIG_DELAY,
+        DappError::InvalidConfigDelay
+    );
+
+    let protocol = &mut ctx.accounts.protocol;
+
+    protocol.guardian = guardian;
+    protocol.fee_receiver = fee_receiver;
+    protocol.config_delay = config_delay;
+    protocol.forbidden_extensions = forbidden_extensions;
+
+    emit_protocol_config(protocol)
+}
@@ -1,18 +1,29 @@
-use std::f64::consts::E;
-
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{self, Mint, TokenAccount, TokenInterface},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::{Bank, DappError, User};
+use crate::{
+    burn_receipts, compute_health, get_bank_price, settle_position_rewards, transfer_tokens, Bank,
+    DappError, Protocol, RewardPool, RewardSide, Rounding, User, WithdrawEvent, PAUSE_WITHDRAW,
+};
 
+// remaining_accounts: [bank, price_update, fallback_feed] por cada posicion del usuario si tiene deuda
+// (ver health.rs)
 #[derive(Accounts)]
 pub struct WithdrawToken<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [b"protocol".as_ref()],
+        bump,
+        constraint = !protocol.is_paused(PAUSE_WITHDRAW) @ DappError::OperationPaused
+    )]
+    pub protocol: Account<'info, Protocol>,
+
     #[account(
         mut,
         seeds = [signer.key().as_ref()],
@@ -31,7 +42,8 @@ pub struct WithdrawToken<'info> {
     #[account(
         mut,
         seeds = [mint_account.key().as_ref()],
-        bump
+        bump,
+        constraint = bank.status.allows_reduce() @ DappError::BankFrozen
     )]
     pub bank: Account<'info, Bank>,
 
@@ -42,6 +54,32 @@ pub struct WithdrawToken<'info> {
     )]
     pub bank_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"receipt".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"collateral".as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub collateral_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // obligatorio si el bank tiene reward pool de depositos
+    #[account(
+        mut,
+        address = bank.deposit_reward_pool @ DappError::InvalidRewardPool
+    )]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+
+    // oracle del bank, el precio se registra en el evento
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+    /// CHECK: validado contra bank.oracle.fallback en get_bank_price
+    pub fallback_feed: UncheckedAccount<'info>,
+
     pub mint_account: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -49,38 +87,61 @@ pub struct WithdrawToken<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn process_withdraw_token(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+pub fn process_withdraw_token<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawToken<'info>>,
+    amount: u64,
+) -> Result<()> {
     // check deposited token user
+    let bank_key = ctx.accounts.bank.key();
     let user = &mut ctx.accounts.user;
     let bank = &mut ctx.accounts.bank;
 
-    let now = Clock::get()?.unix_timestamp;
-    let time_diff = now - bank.last_updated;
-
-    // tasa de interes anual
-    let annual_rate = 0.05; // 5%
-    let seconds_per_year = 365.0 * 24.0 * 60.0 * 60.0; // 31_536_000
-    let rate_per_second = annual_rate / seconds_per_year;
+    // actualizar los indices del bank
+    let clock = Clock::get()?;
+    bank.accrue_interest(clock.unix_timestamp)?;
+
+    let position = user.position_mut(&bank_key)?;
+    settle_position_rewards(
+        bank,
+        RewardSide::Deposit,
+        ctx.accounts.reward_pool.as_mut(),
+        position,
+        clock.unix_timestamp,
+    )?;
 
-    // aplicar interes compuesto continuo al total depositado
-    let compounded = (bank.total_deposited as f64) * E.powf(rate_per_second * time_diff as f64);
-    bank.total_deposited = compounded as u64;
+    // valor actual que posee el usuario -> shares * valor por share
+    let user_value = bank.deposit_shares_to_amount(position.deposit_shares)?;
 
-    // calcular valor por share
-    let value_per_share = bank.total_deposited as f64 / bank.total_deposit_shares as f64;
+    // verificar si puede retirar esa cantidad
+    require!(amount <= user_value, DappError::InsufficientBalance);
 
-    // obtener los shares del usuario para ese token
-    let user_shares = if ctx.accounts.mint_account.key() == user.usdc_address.key() {
-        user.deposited_usdc_shares
+    // shares a quemar -> redondeo hacia arriba, nunca se retira mas de lo que vale
+    let shares_to_remove = if amount == user_value {
+        position.deposit_shares
     } else {
-        user.deposited_sol_shares
+        bank.amount_to_deposit_shares(amount, Rounding::Up)?
     };
 
-    // valor actual que posee el usuario
-    let user_value = (user_shares as f64) * value_per_share;
-
-    // verificar si puede retirar esa cantidad
-    require!(amount as f64 <= user_value, DappError::InsufficientBalance);
+    position.deposit_shares = position
+        .deposit_shares
+        .checked_sub(shares_to_remove)
+        .ok_or(DappError::InsufficientBalance)?;
+    user.remove_empty_positions();
+
+    bank.total_deposited = bank
+        .total_deposited
+        .checked_sub(amount)
+        .ok_or(DappError::InsufficientBalance)?;
+    bank.total_deposit_shares = bank
+        .total_deposit_shares
+        .checked_sub(shares_to_remove)
+        .ok_or(DappError::InsufficientBalance)?;
+
+    // si tiene deuda, el colateral restante tiene que seguir cubriendola
+    if user.has_debt() {
+        let health = compute_health(user, ctx.remaining_accounts, &[(bank_key, bank)], &clock)?;
+        require!(health.is_healthy(), DappError::UnhealthyPosition);
+    }
 
     let mint_key = ctx.accounts.mint_account.key();
     // seeds = [b"treasury".as_ref(), mint_account.key().as_ref()],
@@ -90,40 +151,54 @@ pub fn process_withdraw_token(ctx: Context<WithdrawToken>, amount: u64) -> Resul
         &[ctx.bumps.bank_token_account],
     ]];
 
+    let collateral_seeds: &[&[&[u8]]] = &[&[
+        b"collateral".as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ]];
+    burn_receipts(
+        &ctx.accounts.token_program,
+        &ctx.accounts.receipt_mint,
+        &ctx.accounts.collateral_vault,
+        shares_to_remove,
+        collateral_seeds,
+    )?;
+
     // transfer token bank -> user
-    token_interface::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            token_interface::TransferChecked {
-                from: ctx.accounts.bank_token_account.to_account_info(),
-                mint: ctx.accounts.mint_account.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.bank_token_account.to_account_info(),
-            },
-            signer_seeds,
-        ),
+    transfer_tokens(
+        &ctx.accounts.token_program,
+        ctx.accounts.bank_token_account.to_account_info(),
+        &ctx.accounts.mint_account,
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.bank_token_account.to_account_info(),
+        ctx.remaining_accounts,
         amount,
-        ctx.accounts.mint_account.decimals,
+        signer_seeds,
     )?;
 
-    // update bank and user accounts
-    let shares_to_remove =
-        (amount as f64 / bank.total_deposited as f64) * bank.total_deposit_shares as f64;
-
-    if ctx.accounts.mint_account.key() == user.usdc_address {
-        user.deposited_usdc -= amount;
-        user.deposited_usdc_shares -= shares_to_remove as u64
-    } else {
-        user.deposited_sol -= amount;
-        user.deposited_sol_shares -= shares_to_remove as u64
-    };
-
-    let timestamp = Clock::get()?.unix_timestamp;
+    // liquidez con la que se calcula la utilizacion del proximo periodo
+    ctx.accounts.bank_token_account.reload()?;
+    ctx.accounts
+        .bank
+        .sync_liquidity(ctx.accounts.bank_token_account.amount);
+
+    let price = get_bank_price(
+        &ctx.accounts.bank,
+        &ctx.accounts.price_update,
+        &ctx.accounts.fallback_feed,
+        &clock,
+    )?;
 
-    bank.total_deposited -= amount;
-    bank.total_deposit_shares -= shares_to_remove as u64;
-    bank.last_updated = timestamp;
-    user.last_updated = timestamp;
+    emit!(WithdrawEvent {
+        user: ctx.accounts.signer.key(),
+        bank: bank_key,
+        amount,
+        shares: shares_to_remove,
+        cumulative_supply_index: ctx.accounts.bank.cumulative_supply_index,
+        price: price.price,
+        price_exponent: price.exponent,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
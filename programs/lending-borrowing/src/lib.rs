@@ -1,14 +1,24 @@
 pub mod constants;
 pub mod errors;
+pub mod events;
+pub mod health;
 pub mod instructions;
+pub mod math;
+pub mod oracle;
 pub mod state;
+pub mod valuation;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
 pub use errors::*;
+pub use events::*;
+pub use health::*;
 pub use instructions::*;
+pub use math::*;
+pub use oracle::*;
 pub use state::*;
+pub use valuation::*;
 
 declare_id!("H4f3DqtsxbBqURCSR6fqs6N5J4hUUj6VhgwzzVWbGiD7");
 
@@ -16,35 +26,185 @@ declare_id!("H4f3DqtsxbBqURCSR6fqs6N5J4hUUj6VhgwzzVWbGiD7");
 pub mod lending_borrowing {
     use super::*;
 
+    pub fn initialize_protocol(
+        ctx: Context<InitializeProtocol>,
+        guardian: Pubkey,
+        fee_receiver: Pubkey,
+    ) -> Result<()> {
+        initialize_protocol::process_initialize_protocol(ctx, guardian, fee_receiver)
+    }
+
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        guardian: Pubkey,
+        fee_receiver: Pubkey,
+        config_delay: i64,
+        forbidden_extensions: u64,
+    ) -> Result<()> {
+        update_protocol_config::process_update_protocol_config(
+            ctx,
+            guardian,
+            fee_receiver,
+            config_delay,
+            forbidden_extensions,
+        )
+    }
+
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        transfer_admin::process_transfer_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        accept_admin::process_accept_admin(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        set_paused::process_set_paused(ctx, paused)
+    }
+
+    pub fn set_bank_status(ctx: Context<SetBankStatus>, status: BankStatus) -> Result<()> {
+        set_bank_status::process_set_bank_status(ctx, status)
+    }
+
     pub fn initialize_bank(
         ctx: Context<InitializeBank>,
         liquidation_threshold: u64,
         max_ltv: u64,
+        oracle: OracleConfig,
     ) -> Result<()> {
-        initialize_bank::process_initialize_bank(ctx, liquidation_threshold, max_ltv)
+        initialize_bank::process_initialize_bank(ctx, liquidation_threshold, max_ltv, oracle)
     }
 
-    pub fn intialize_user(ctx: Context<InitializeUser>, usdc_address: Pubkey) -> Result<()> {
-        initialize_user::process_initialize_user(ctx, usdc_address)
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
+        params: BankConfigParams,
+    ) -> Result<()> {
+        queue_config_change::process_queue_config_change(ctx, params)
     }
 
-    pub fn deposit_token(ctx: Context<DepositToken>, amount: u64) -> Result<()> {
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        execute_config_change::process_execute_config_change(ctx)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        cancel_config_change::process_cancel_config_change(ctx)
+    }
+
+    pub fn intialize_user(ctx: Context<InitializeUser>) -> Result<()> {
+        initialize_user::process_initialize_user(ctx)
+    }
+
+    pub fn deposit_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         deposit_token::process_deposit_token(ctx, amount)
     }
 
-    pub fn withdraw_token(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+    pub fn withdraw_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         withdraw_token::process_withdraw_token(ctx, amount)
     }
 
-    pub fn borrow_token(ctx: Context<BorrowToken>, amount: u64) -> Result<()> {
+    pub fn lock_collateral(ctx: Context<LockCollateral>, shares: u64) -> Result<()> {
+        lock_collateral::process_lock_collateral(ctx, shares)
+    }
+
+    pub fn unlock_collateral(ctx: Context<UnlockCollateral>, shares: u64) -> Result<()> {
+        unlock_collateral::process_unlock_collateral(ctx, shares)
+    }
+
+    pub fn borrow_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, BorrowToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         borrow_token::process_borrow_token(ctx, amount)
     }
 
-    pub fn repay_token(ctx: Context<RepayToken>, amount: u64) -> Result<()> {
+    pub fn repay_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, RepayToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         repay_token::process_repay_token(ctx, amount)
     }
 
-    pub fn liquidate_position(ctx: Context<LiquidatePosition>) -> Result<()> {
-        liquidate_position::process_liquidate_position(ctx)
+    pub fn create_reward_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateRewardPool<'info>>,
+        side: RewardSide,
+        emission_rate: u64,
+        start_time: i64,
+        end_time: i64,
+        funding: u64,
+    ) -> Result<()> {
+        create_reward_pool::process_create_reward_pool(
+            ctx,
+            side,
+            emission_rate,
+            start_time,
+            end_time,
+            funding,
+        )
+    }
+
+    pub fn claim_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>,
+        side: RewardSide,
+    ) -> Result<()> {
+        claim_rewards::process_claim_rewards(ctx, side)
+    }
+
+    pub fn flash_borrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlashBorrow<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        flash_borrow::process_flash_borrow(ctx, amount)
+    }
+
+    pub fn flash_repay<'info>(ctx: Context<'_, '_, '_, 'info, FlashRepay<'info>>) -> Result<()> {
+        flash_repay::process_flash_repay(ctx)
+    }
+
+    pub fn refresh_bank(ctx: Context<RefreshBank>) -> Result<()> {
+        refresh_bank::process_refresh_bank(ctx)
+    }
+
+    pub fn collect_fees<'info>(ctx: Context<'_, '_, '_, 'info, CollectFees<'info>>) -> Result<()> {
+        collect_fees::process_collect_fees(ctx)
+    }
+
+    pub fn liquidate_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, LiquidatePosition<'info>>,
+        repay_amount: u64,
+        min_collateral_out: u64,
+    ) -> Result<()> {
+        liquidate_position::process_liquidate_position(ctx, repay_amount, min_collateral_out)
+    }
+
+    pub fn settle_bad_debt<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleBadDebt<'info>>,
+    ) -> Result<()> {
+        settle_bad_debt::process_settle_bad_debt(ctx)
+    }
+
+    pub fn stake_insurance<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeInsurance<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        stake_insurance::process_stake_insurance(ctx, amount)
+    }
+
+    pub fn request_unstake_insurance(
+        ctx: Context<RequestUnstakeInsurance>,
+        shares: u64,
+    ) -> Result<()> {
+        request_unstake_insurance::process_request_unstake_insurance(ctx, shares)
+    }
+
+    pub fn unstake_insurance<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnstakeInsurance<'info>>,
+    ) -> Result<()> {
+        unstake_insurance::process_unstake_insurance(ctx)
     }
 }
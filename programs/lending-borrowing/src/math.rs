@@ -0,0 +1,294 @@
+use anchor_lang::prelude::*;
+
+use crate::DappError;
+
+use self::wide::U256;
+
+// fuera del scope del prelude de anchor, que redefine `Result`
+#[allow(clippy::all)]
+mod wide {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+
+/// 1.0 expressed in WAD fixed point (18 decimals).
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60; // 31_536_000
+
+// suficiente para que la serie converja una vez que x < 1
+const MAX_EXP_TERMS: u128 = 32;
+
+/// Direction in which a lossy operation truncates. Amounts owed to the protocol
+/// (debt) round `Up`, amounts owed to users (deposits) round `Down`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Unsigned fixed-point number with 18 decimals backed by a `u128`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Wad(pub u128);
+
+impl Wad {
+    pub const ZERO: Wad = Wad(0);
+    pub const ONE: Wad = Wad(WAD);
+
+    pub fn from_u64(value: u64) -> Self {
+        // u64::MAX * WAD < u128::MAX, no puede desbordar
+        Wad(value as u128 * WAD)
+    }
+
+    pub fn from_bps(bps: u64) -> Self {
+        Wad(bps as u128 * WAD / BPS_DENOMINATOR as u128)
+    }
+
+    pub fn from_ratio(numerator: u128, denominator: u128, rounding: Rounding) -> Result<Self> {
+        mul_div_u128(numerator, WAD, denominator, rounding).map(Wad)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, rhs: Wad) -> Result<Wad> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Wad)
+            .ok_or(error!(DappError::MathOverflow))
+    }
+
+    pub fn checked_sub(self, rhs: Wad) -> Result<Wad> {
+        self.0
+            .checked_sub(rhs.0)
+            .map(Wad)
+            .ok_or(error!(DappError::MathOverflow))
+    }
+
+    pub fn checked_mul(self, rhs: Wad, rounding: Rounding) -> Result<Wad> {
+        mul_div_u128(self.0, rhs.0, WAD, rounding).map(Wad)
+    }
+
+    pub fn checked_div(self, rhs: Wad, rounding: Rounding) -> Result<Wad> {
+        mul_div_u128(self.0, WAD, rhs.0, rounding).map(Wad)
+    }
+
+    pub fn to_u64(self, rounding: Rounding) -> Result<u64> {
+        let value = match rounding {
+            Rounding::Down => self.0 / WAD,
+            Rounding::Up => self.0.div_ceil(WAD),
+        };
+        u64::try_from(value).map_err(|_| error!(DappError::MathOverflow))
+    }
+
+    /// e^self. The argument is halved until it is below 1 so the Taylor series
+    /// converges quickly, and the partial result is squared back up. Every step
+    /// truncates, so the result never overstates the true value.
+    pub fn exp(self) -> Result<Wad> {
+        let mut x = self.0;
+        let mut halvings = 0u32;
+        while x > WAD {
+            x /= 2;
+            halvings += 1;
+        }
+
+        let x = U256::from(x);
+        let wad = U256::from(WAD);
+        let mut sum = wad;
+        let mut term = wad;
+        for n in 1..=MAX_EXP_TERMS {
+            term = term * x / (wad * U256::from(n));
+            if term.is_zero() {
+                break;
+            }
+            sum += term;
+        }
+
+        let mut result = Wad(sum.as_u128());
+        for _ in 0..halvings {
+            result = result.checked_mul(result, Rounding::Down)?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// a * b / c with a 256-bit intermediate.
+pub fn mul_div_u128(a: u128, b: u128, c: u128, rounding: Rounding) -> Result<u128> {
+    require!(c != 0, DappError::MathOverflow);
+
+    let product = U256::from(a) * U256::from(b);
+    let divisor = U256::from(c);
+    let mut quotient = product / divisor;
+    if rounding == Rounding::Up && !(product % divisor).is_zero() {
+        quotient += U256::one();
+    }
+
+    require!(quotient <= U256::from(u128::MAX), DappError::MathOverflow);
+    Ok(quotient.as_u128())
+}
+
+/// a * b / c for token amounts and shares.
+pub fn mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> Result<u64> {
+    let value = mul_div_u128(a as u128, b as u128, c as u128, rounding)?;
+    u64::try_from(value).map_err(|_| error!(DappError::MathOverflow))
+}
+
+/// Continuous compounding factor e^(rate * t) for an annual rate over
+/// `elapsed` seconds.
+pub fn compound_factor(annual_rate: Wad, elapsed: u64) -> Result<Wad> {
+    let exponent = mul_div_u128(
+        annual_rate.0,
+        elapsed as u128,
+        SECONDS_PER_YEAR as u128,
+        Rounding::Down,
+    )?;
+
+    Wad(exponent).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // |actual - expected| <= expected * tolerance_wad / WAD
+    fn assert_close(actual: Wad, expected: u128, tolerance_wad: u128) {
+        let diff = actual.0.abs_diff(expected);
+        let max_diff = mul_div_u128(expected, tolerance_wad, WAD, Rounding::Up).unwrap();
+        assert!(
+            diff <= max_diff,
+            "actual {} expected {} diff {}",
+            actual.0,
+            expected,
+            diff
+        );
+    }
+
+    #[test]
+    fn mul_div_rounds_in_both_directions() {
+        assert_eq!(mul_div(10, 1, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(10, 1, 3, Rounding::Up).unwrap(), 4);
+        // exacto: los dos redondeos coinciden
+        assert_eq!(mul_div(9, 1, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up).unwrap(), 3);
+        assert_eq!(mul_div_u128(7, 5, 2, Rounding::Down).unwrap(), 17);
+        assert_eq!(mul_div_u128(7, 5, 2, Rounding::Up).unwrap(), 18);
+    }
+
+    #[test]
+    fn mul_div_uses_wide_intermediate() {
+        // a * b no entra en u128 pero el resultado si
+        let a = u128::MAX / 3;
+        assert_eq!(mul_div_u128(a, 6, 3, Rounding::Down).unwrap(), a * 2);
+    }
+
+    #[test]
+    fn mul_div_errors_instead_of_overflowing() {
+        assert!(mul_div_u128(u128::MAX, 2, 1, Rounding::Down).is_err());
+        assert!(mul_div_u128(1, 1, 0, Rounding::Down).is_err());
+        assert!(mul_div(u64::MAX, 2, 1, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn wad_conversions() {
+        assert_eq!(Wad::from_u64(3).0, 3 * WAD);
+        assert_eq!(Wad::from_bps(2_500).0, WAD / 4);
+        assert_eq!(
+            Wad::from_ratio(1, 3, Rounding::Down).unwrap().0,
+            333_333_333_333_333_333
+        );
+        assert_eq!(
+            Wad::from_ratio(1, 3, Rounding::Up).unwrap().0,
+            333_333_333_333_333_334
+        );
+        assert_eq!(Wad(WAD + 1).to_u64(Rounding::Down).unwrap(), 1);
+        assert_eq!(Wad(WAD + 1).to_u64(Rounding::Up).unwrap(), 2);
+        assert!(Wad(u128::MAX).to_u64(Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn wad_arithmetic() {
+        let half = Wad::from_bps(5_000);
+        let third = Wad::from_ratio(1, 3, Rounding::Down).unwrap();
+
+        assert_eq!(
+            half.checked_mul(half, Rounding::Down).unwrap(),
+            Wad::from_bps(2_500)
+        );
+        assert_eq!(
+            Wad::ONE
+                .checked_div(Wad::from_u64(3), Rounding::Down)
+                .unwrap(),
+            third
+        );
+        assert_eq!(
+            Wad::ONE
+                .checked_div(Wad::from_u64(3), Rounding::Up)
+                .unwrap(),
+            Wad(third.0 + 1)
+        );
+        assert_eq!(
+            third.checked_mul(Wad(1), Rounding::Down).unwrap(),
+            Wad::ZERO
+        );
+        assert_eq!(third.checked_mul(Wad(1), Rounding::Up).unwrap(), Wad(1));
+
+        assert!(Wad::ZERO.checked_sub(Wad(1)).is_err());
+        assert!(Wad(u128::MAX).checked_add(Wad(1)).is_err());
+        assert!(Wad(u128::MAX)
+            .checked_mul(Wad::from_u64(2), Rounding::Down)
+            .is_err());
+        assert!(Wad::ONE.checked_div(Wad::ZERO, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn exp_matches_known_values() {
+        assert_eq!(Wad::ZERO.exp().unwrap(), Wad::ONE);
+
+        // e, e^0.5, e^2 y e^10 con 18 decimales
+        assert_close(Wad::ONE.exp().unwrap(), 2_718_281_828_459_045_235, 1_000);
+        assert_close(
+            Wad::from_bps(5_000).exp().unwrap(),
+            1_648_721_270_700_128_146,
+            1_000,
+        );
+        assert_close(
+            Wad::from_u64(2).exp().unwrap(),
+            7_389_056_098_930_650_227,
+            1_000,
+        );
+        assert_close(
+            Wad::from_u64(10).exp().unwrap(),
+            22_026_465_794_806_716_516_957,
+            1_000_000,
+        );
+    }
+
+    #[test]
+    fn exp_never_overstates() {
+        assert!(Wad::ONE.exp().unwrap().0 <= 2_718_281_828_459_045_236);
+        assert!(Wad::from_u64(2).exp().unwrap().0 <= 7_389_056_098_930_650_228);
+    }
+
+    #[test]
+    fn compound_factor_matches_continuous_compounding() {
+        // 10% anual durante un año -> e^0.1
+        let rate = Wad::from_bps(1_000);
+        assert_close(
+            compound_factor(rate, SECONDS_PER_YEAR).unwrap(),
+            1_105_170_918_075_647_624,
+            1_000,
+        );
+        // medio año -> e^0.05
+        assert_close(
+            compound_factor(rate, SECONDS_PER_YEAR / 2).unwrap(),
+            1_051_271_096_376_024_039,
+            1_000,
+        );
+        assert_eq!(compound_factor(rate, 0).unwrap(), Wad::ONE);
+    }
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2, VerificationLevel};
+use switchboard_on_demand::{PullFeedAccountData, SWITCHBOARD_ON_DEMAND_PROGRAM_ID};
+
+use crate::{
+    price_to_wad, Bank, DappError, OracleConfig, OracleType, Rounding, Wad, BPS_DENOMINATOR,
+};
+
+// switchboard publica con 18 decimales, que no entran en un i64; se lleva a 8
+const SWITCHBOARD_EXPONENT: i32 = -8;
+const SWITCHBOARD_SCALE: i128 = 10_000_000_000; // 10^(18 - 8)
+
+/// Price of the bank token. The primary (Pyth) price is used unless it is
+/// stale or too uncertain, in which case the fallback feed is used if
+/// configured. When both are usable they must agree within `max_divergence`.
+/// `fallback_feed` is ignored if the bank has no fallback configured.
+pub fn get_bank_price(
+    bank: &Bank,
+    price_update: &PriceUpdateV2,
+    fallback_feed: &AccountInfo,
+    clock: &Clock,
+) -> Result<Price> {
+    let oracle = &bank.oracle;
+
+    let primary = get_oracle_price(oracle.oracle_type, oracle, price_update, fallback_feed)?;
+    let primary_check = check_price(&primary, oracle, clock);
+
+    let Some(fallback) = oracle.fallback else {
+        primary_check?;
+        return Ok(primary);
+    };
+
+    require_keys_eq!(
+        fallback_feed.key(),
+        fallback.feed,
+        DappError::InvalidOracleAccount
+    );
+    let secondary = get_oracle_price(fallback.oracle_type, oracle, price_update, fallback_feed)?;
+    let secondary_check = check_price(&secondary, oracle, clock);
+
+    match (primary_check, secondary_check) {
+        (Ok(()), Ok(())) => {
+            check_divergence(&primary, &secondary, fallback.max_divergence)?;
+            Ok(primary)
+        }
+        (Err(_), Ok(())) => Ok(secondary),
+        (Ok(()), Err(_)) => Ok(primary),
+        (Err(err), Err(_)) => Err(err),
+    }
+}
+
+// lee el precio sin validar antiguedad ni confianza; una cuenta incorrecta es un error,
+// no un motivo para pasar al fallback
+fn get_oracle_price(
+    oracle_type: OracleType,
+    oracle: &OracleConfig,
+    price_update: &PriceUpdateV2,
+    fallback_feed: &AccountInfo,
+) -> Result<Price> {
+    match oracle_type {
+        OracleType::PythPull => {
+            require!(
+                price_update.verification_level.gte(VerificationLevel::Full),
+                DappError::InvalidOracleAccount
+            );
+            Ok(price_update.get_price_unchecked(&oracle.feed_id)?)
+        }
+        OracleType::SwitchboardPull => get_switchboard_price(fallback_feed),
+    }
+}
+
+fn get_switchboard_price(info: &AccountInfo) -> Result<Price> {
+    require_keys_eq!(
+        *info.owner,
+        SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+
+    let feed = PullFeedAccountData::parse(info.try_borrow_data()?)
+        .map_err(|_| DappError::InvalidOracleAccount)?;
+    require!(feed.result.slot != 0, DappError::InvalidOracleAccount);
+
+    let price = feed.result.value / SWITCHBOARD_SCALE;
+    let conf = (feed.result.std_dev + SWITCHBOARD_SCALE - 1) / SWITCHBOARD_SCALE;
+
+    Ok(Price {
+        price: i64::try_from(price).map_err(|_| DappError::InvalidPrice)?,
+        conf: u64::try_from(conf).map_err(|_| DappError::InvalidPrice)?,
+        exponent: SWITCHBOARD_EXPONENT,
+        publish_time: feed.last_update_timestamp,
+    })
+}
+
+// antiguedad y confianza, mismos limites para ambos oracles
+fn check_price(price: &Price, oracle: &OracleConfig, clock: &Clock) -> Result<()> {
+    require!(
+        price
+            .publish_time
+            .saturating_add(oracle.max_staleness as i64)
+            >= clock.unix_timestamp,
+        DappError::PriceTooStale
+    );
+
+    check_confidence(price, oracle.max_confidence)
+}
+
+// conf / price <= max_confidence (basis points)
+pub fn check_confidence(price: &Price, max_confidence: u64) -> Result<()> {
+    require!(price.price > 0, DappError::InvalidPrice);
+
+    let conf = price.conf as u128 * BPS_DENOMINATOR as u128;
+    let max_conf = price.price as u128 * max_confidence as u128;
+    require!(conf <= max_conf, DappError::PriceConfidenceTooWide);
+
+    Ok(())
+}
+
+// |primary - secondary| / primary <= max_divergence (basis points)
+fn check_divergence(primary: &Price, secondary: &Price, max_divergence: u64) -> Result<()> {
+    let primary = price_to_wad(primary, Rounding::Down)?;
+    let secondary = price_to_wad(secondary, Rounding::Down)?;
+
+    let diff = primary.max(secondary).checked_sub(primary.min(secondary))?;
+    require!(
+        diff <= primary.checked_mul(Wad::from_bps(max_divergence), Rounding::Down)?,
+        DappError::OracleDivergence
+    );
+
+    Ok(())
+}
+
+// price - conf, el colateral nunca se sobrevalua
+pub fn collateral_price(price: &Price) -> Price {
+    Price {
+        price: price.price.saturating_sub_unsigned(price.conf),
+        ..*price
+    }
+}
+
+// price + conf, la deuda nunca se subvalua
+pub fn debt_price(price: &Price) -> Price {
+    Price {
+        price: price.price.saturating_add_unsigned(price.conf),
+        ..*price
+    }
+}
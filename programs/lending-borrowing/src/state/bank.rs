@@ -1,20 +1,508 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    compound_factor, mul_div, utilization, DappError, InterestRateModel, OracleConfig, RewardSide,
+    Rounding, Wad, BPS_DENOMINATOR, MAX_FLASH_LOAN_FEE, MAX_LIQUIDATION_BONUS, MIN_SUPPLY_INDEX,
+};
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum BankStatus {
+    #[default]
+    Active,
+    ReduceOnly, // solo repay, withdraw y liquidaciones
+    Frozen,     // ninguna operacion de usuarios
+}
+
+impl BankStatus {
+    // deposit y borrow
+    pub fn allows_increase(&self) -> bool {
+        *self == BankStatus::Active
+    }
+
+    // withdraw, repay y liquidaciones
+    pub fn allows_reduce(&self) -> bool {
+        *self != BankStatus::Frozen
+    }
+}
+
+// parametros de riesgo a modificar, None deja el valor actual
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct BankConfigParams {
+    pub max_ltv: Option<u64>,
+    pub liquidation_threshold: Option<u64>,
+    pub liquidation_bonus: Option<u64>,
+    pub liquidation_close_factor: Option<u64>,
+    pub flash_loan_fee: Option<u64>,
+    pub rate_model: Option<InterestRateModel>,
+    pub reserve_factor: Option<u64>,
+    pub oracle: Option<OracleConfig>,
+    pub deposit_limit: Option<u64>, // 0 = sin limite
+    pub borrow_limit: Option<u64>,
+    pub insurance_fee: Option<u64>,
+    pub insurance_cooldown: Option<i64>,
+    pub insurance_unstake_window: Option<i64>,
+}
+
 // para cada token. sera la cuenta que reciba y envie los tokens
 #[account]
 #[derive(InitSpace)]
 pub struct Bank {
-    pub authority: Pubkey,
     pub mint_address: Pubkey,
+    pub mint_decimals: u8,
     pub total_deposited: u64,
     pub total_deposit_shares: u64, //
     pub total_borrowed: u64,
     pub total_borrow_shares: u64,
-    pub liquidation_threshold: u64, // porcentaje del valor colateral a partir del cual un prestamo puede ser liquidado
+    pub liquidation_threshold: u64, // porcentaje del valor colateral a partir del cual un prestamo puede ser liquidado (basis points)
     pub liquidation_bonus: u64, // incentivo que recibe el liquidador por ejecutar una liquidacion
     pub liquidation_close_factor: u64, // porcentaje maximo de la deuda que se puede liquidar en una unica liquidacion
-    pub max_ltv: u64,                  // Loan-To-Value -> maximo permitido al tomar prestado
+    pub max_ltv: u64, // Loan-To-Value -> maximo permitido al tomar prestado (basis points)
     pub last_updated: i64,
-    pub last_updated_borrowed: i64,
-    pub interest_rate: u64,
+    pub cumulative_borrow_index: u128, // WAD, crece con el interes compuesto de los prestamos
+    pub cumulative_supply_index: u128, // WAD, crece con el interes que reciben los depositantes
+    pub rate_model: InterestRateModel,
+    pub reserve_factor: u64, // porcentaje del interes que va al protocolo, en basis points
+    pub accumulated_protocol_fees: u64, // fees acumulados en el treasury, pendientes de cobrar
+    pub insurance_fee: u64, // porcentaje de los fees del protocolo que va al insurance fund (basis points)
+    pub accumulated_insurance_fees: u64, // fees del insurance fund todavia en el treasury
+    pub total_insurance_shares: u64,
+    pub insurance_epoch: u64, // sube cuando un slashing vacia el fondo, invalida las shares anteriores
+    pub insurance_cooldown: i64, // segundos entre pedir el unstake y poder retirarlo
+    pub insurance_unstake_window: i64, // segundos despues del cooldown en los que se puede retirar
+    pub oracle: OracleConfig,
+    pub status: BankStatus,
+    pub deposit_limit: u64,     // maximo de total_deposited, 0 = sin limite
+    pub borrow_limit: u64,      // maximo de total_borrowed, 0 = sin limite
+    pub flash_loan_fee: u64,    // basis points sobre el monto del flash loan
+    pub flash_loan_amount: u64, // flash loan en curso, 0 = ninguno
+    pub deposit_reward_pool: Pubkey, // Pubkey::default() = sin rewards
+    pub borrow_reward_pool: Pubkey,
+    pub unrecovered_bad_debt: u64, // bad debt que no cubrio el insurance, el protocolo ni los depositantes
+    pub liquidity: u64, // treasury sin fees pendientes al final de la ultima instruccion que lo movio
+}
+
+impl Bank {
+    pub fn apply_config(&mut self, params: &BankConfigParams) {
+        if let Some(max_ltv) = params.max_ltv {
+            self.max_ltv = max_ltv;
+        }
+        if let Some(liquidation_threshold) = params.liquidation_threshold {
+            self.liquidation_threshold = liquidation_threshold;
+        }
+        if let Some(liquidation_bonus) = params.liquidation_bonus {
+            self.liquidation_bonus = liquidation_bonus;
+        }
+        if let Some(liquidation_close_factor) = params.liquidation_close_factor {
+            self.liquidation_close_factor = liquidation_close_factor;
+        }
+        if let Some(flash_loan_fee) = params.flash_loan_fee {
+            self.flash_loan_fee = flash_loan_fee;
+        }
+        if let Some(rate_model) = params.rate_model {
+            self.rate_model = rate_model;
+        }
+        if let Some(reserve_factor) = params.reserve_factor {
+            self.reserve_factor = reserve_factor;
+        }
+        if let Some(oracle) = params.oracle {
+            self.oracle = oracle;
+        }
+        if let Some(deposit_limit) = params.deposit_limit {
+            self.deposit_limit = deposit_limit;
+        }
+        if let Some(borrow_limit) = params.borrow_limit {
+            self.borrow_limit = borrow_limit;
+        }
+        if let Some(insurance_fee) = params.insurance_fee {
+            self.insurance_fee = insurance_fee;
+        }
+        if let Some(insurance_cooldown) = params.insurance_cooldown {
+            self.insurance_cooldown = insurance_cooldown;
+        }
+        if let Some(insurance_unstake_window) = params.insurance_unstake_window {
+            self.insurance_unstake_window = insurance_unstake_window;
+        }
+    }
+
+    // ltv < threshold <= 100%, bonus acotado, close factor en (0, 100%], flash fee acotado,
+    // modelo de tasas, oracle e insurance validos
+    pub fn validate_config(&self) -> Result<()> {
+        require!(
+            self.max_ltv < self.liquidation_threshold
+                && self.liquidation_threshold <= BPS_DENOMINATOR,
+            DappError::InvalidBankConfig
+        );
+        require!(
+            self.liquidation_bonus <= MAX_LIQUIDATION_BONUS,
+            DappError::InvalidBankConfig
+        );
+        // colateral a threshold + bonus no puede superar el valor del colateral
+        require!(
+            mul_div(
+                self.liquidation_threshold,
+                BPS_DENOMINATOR + self.liquidation_bonus,
+                BPS_DENOMINATOR,
+                Rounding::Up,
+            )? <= BPS_DENOMINATOR,
+            DappError::InvalidBankConfig
+        );
+        require!(
+            self.liquidation_close_factor > 0 && self.liquidation_close_factor <= BPS_DENOMINATOR,
+            DappError::InvalidBankConfig
+        );
+        require!(
+            self.flash_loan_fee <= MAX_FLASH_LOAN_FEE,
+            DappError::InvalidFlashLoanFee
+        );
+
+        self.rate_model.validate()?;
+        require!(
+            self.reserve_factor < BPS_DENOMINATOR,
+            DappError::InvalidReserveFactor
+        );
+        self.oracle.validate()?;
+        require!(
+            self.insurance_fee <= BPS_DENOMINATOR
+                && self.insurance_cooldown >= 0
+                && self.insurance_unstake_window > 0,
+            DappError::InvalidInsuranceConfig
+        );
+
+        Ok(())
+    }
+
+    // avanza los indices globales hasta `now` y recalcula los totales
+    pub fn accrue_interest(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_updated);
+        if elapsed <= 0 {
+            return Ok(());
+        }
+
+        // la tasa se fija con la utilizacion al inicio del periodo
+        let utilization = utilization(self.total_borrowed, self.liquidity)?;
+        let borrow_rate = self.rate_model.borrow_rate(utilization)?;
+        let growth = compound_factor(borrow_rate, elapsed as u64)?;
+
+        let borrow_index = Wad(self.cumulative_borrow_index).checked_mul(growth, Rounding::Up)?;
+        self.cumulative_borrow_index = borrow_index.0;
+
+        let total_borrowed = self.borrow_shares_to_amount(self.total_borrow_shares)?;
+        let interest = total_borrowed.saturating_sub(self.total_borrowed);
+        self.total_borrowed = total_borrowed;
+
+        // el reserve factor va al protocolo, el resto a los depositantes
+        let protocol_fee = mul_div(interest, self.reserve_factor, BPS_DENOMINATOR, Rounding::Up)?;
+        let depositor_interest = interest - protocol_fee;
+
+        self.add_protocol_fee(protocol_fee)?;
+        self.credit_depositors(depositor_interest)?;
+
+        self.last_updated = now;
+
+        Ok(())
+    }
+
+    // reparte `amount` entre los depositantes subiendo el supply index. sin depositantes
+    // no hay a quien acreditarlo y va a los fees del protocolo
+    pub fn credit_depositors(&mut self, amount: u64) -> Result<()> {
+        if self.total_deposit_shares == 0 {
+            return self.add_protocol_fee(amount);
+        }
+
+        let index_delta = Wad::from_ratio(
+            amount as u128,
+            self.total_deposit_shares as u128,
+            Rounding::Down,
+        )?;
+        self.cumulative_supply_index = Wad(self.cumulative_supply_index)
+            .checked_add(index_delta)?
+            .0;
+        self.total_deposited = self.deposit_shares_to_amount(self.total_deposit_shares)?;
+        Ok(())
+    }
+
+    // liquidez del treasury para la utilizacion del proximo periodo, sin los fees pendientes
+    // que no se pueden prestar. se llama despues de mover tokens del treasury
+    pub fn sync_liquidity(&mut self, treasury_amount: u64) {
+        self.liquidity = treasury_amount
+            .saturating_sub(self.accumulated_protocol_fees)
+            .saturating_sub(self.accumulated_insurance_fees);
+    }
+
+    // baja el supply index para repartir `loss` entre los depositantes, como mucho todo lo
+    // depositado y sin bajar de MIN_SUPPLY_INDEX. devuelve la perdida efectivamente socializada
+    pub fn socialize_loss(&mut self, loss: u64) -> Result<u64> {
+        let loss = loss.min(self.total_deposited);
+        if loss == 0 || self.total_deposit_shares == 0 {
+            return Ok(0);
+        }
+
+        let index_delta = Wad::from_ratio(
+            loss as u128,
+            self.total_deposit_shares as u128,
+            Rounding::Up,
+        )?;
+        self.cumulative_supply_index = self
+            .cumulative_supply_index
+            .saturating_sub(index_delta.0)
+            .max(MIN_SUPPLY_INDEX);
+
+        let total_deposited = self.deposit_shares_to_amount(self.total_deposit_shares)?;
+        let socialized = self.total_deposited - total_deposited.min(self.total_deposited);
+        self.total_deposited = total_deposited;
+        Ok(socialized)
+    }
+
+    // una parte de los fees del protocolo financia el insurance fund
+    pub fn add_protocol_fee(&mut self, fee: u64) -> Result<()> {
+        let insurance_fee = mul_div(fee, self.insurance_fee, BPS_DENOMINATOR, Rounding::Down)?;
+        self.accumulated_insurance_fees = self
+            .accumulated_insurance_fees
+            .checked_add(insurance_fee)
+            .ok_or(DappError::MathOverflow)?;
+        self.accumulated_protocol_fees = self
+            .accumulated_protocol_fees
+            .checked_add(fee - insurance_fee)
+            .ok_or(DappError::MathOverflow)?;
+        Ok(())
+    }
+
+    // si el fondo quedo vacio con shares, esas shares ya no valen nada y se descartan
+    // pasando a un nuevo epoch, asi el proximo stake no se divide por cero
+    pub fn reset_depleted_insurance(&mut self, fund_value: u64) {
+        if fund_value == 0 && self.total_insurance_shares > 0 {
+            self.total_insurance_shares = 0;
+            self.insurance_epoch += 1;
+        }
+    }
+
+    pub fn reward_pool(&self, side: RewardSide) -> Pubkey {
+        match side {
+            RewardSide::Deposit => self.deposit_reward_pool,
+            RewardSide::Borrow => self.borrow_reward_pool,
+        }
+    }
+
+    pub fn total_shares(&self, side: RewardSide) -> u64 {
+        match side {
+            RewardSide::Deposit => self.total_deposit_shares,
+            RewardSide::Borrow => self.total_borrow_shares,
+        }
+    }
+
+    pub fn is_within_deposit_limit(&self) -> bool {
+        self.deposit_limit == 0 || self.total_deposited <= self.deposit_limit
+    }
+
+    pub fn is_within_borrow_limit(&self) -> bool {
+        self.borrow_limit == 0 || self.total_borrowed <= self.borrow_limit
+    }
+
+    // (utilizacion, tasa anual de borrow, tasa anual de supply) con los totales actuales
+    pub fn current_rates(&self) -> Result<(Wad, Wad, Wad)> {
+        let utilization = utilization(self.total_borrowed, self.liquidity)?;
+        Ok((
+            utilization,
+            self.rate_model.borrow_rate(utilization)?,
+            self.rate_model
+                .supply_rate(utilization, self.reserve_factor)?,
+        ))
+    }
+
+    // shares * indice
+    pub fn deposit_shares_to_amount(&self, shares: u64) -> Result<u64> {
+        Wad(self.cumulative_supply_index)
+            .checked_mul(Wad::from_u64(shares), Rounding::Down)?
+            .to_u64(Rounding::Down)
+    }
+
+    pub fn borrow_shares_to_amount(&self, shares: u64) -> Result<u64> {
+        Wad(self.cumulative_borrow_index)
+            .checked_mul(Wad::from_u64(shares), Rounding::Up)?
+            .to_u64(Rounding::Up)
+    }
+
+    // amount / indice
+    pub fn amount_to_deposit_shares(&self, amount: u64, rounding: Rounding) -> Result<u64> {
+        Wad::from_u64(amount)
+            .checked_div(Wad(self.cumulative_supply_index), rounding)?
+            .to_u64(rounding)
+    }
+
+    pub fn amount_to_borrow_shares(&self, amount: u64, rounding: Rounding) -> Result<u64> {
+        Wad::from_u64(amount)
+            .checked_div(Wad(self.cumulative_borrow_index), rounding)?
+            .to_u64(rounding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OracleType, WAD};
+
+    // bank con indices en 1 y sin prestamos
+    fn bank(deposited: u64) -> Bank {
+        Bank {
+            mint_address: Pubkey::default(),
+            mint_decimals: 6,
+            total_deposited: deposited,
+            total_deposit_shares: deposited,
+            total_borrowed: 0,
+            total_borrow_shares: 0,
+            liquidation_threshold: 8_000,
+            liquidation_bonus: 500,
+            liquidation_close_factor: 5_000,
+            max_ltv: 7_500,
+            last_updated: 0,
+            cumulative_borrow_index: WAD,
+            cumulative_supply_index: WAD,
+            rate_model: InterestRateModel {
+                base_rate: 0,
+                optimal_utilization: 8_000,
+                slope1: 400,
+                slope2: 7_500,
+            },
+            reserve_factor: 1_000,
+            accumulated_protocol_fees: 0,
+            insurance_fee: 5_000,
+            accumulated_insurance_fees: 0,
+            total_insurance_shares: 0,
+            insurance_epoch: 0,
+            insurance_cooldown: 0,
+            insurance_unstake_window: 1,
+            oracle: OracleConfig {
+                feed_id: [1; 32],
+                oracle_type: OracleType::PythPull,
+                max_staleness: 60,
+                max_confidence: 200,
+                fallback: None,
+            },
+            status: BankStatus::Active,
+            deposit_limit: 0,
+            borrow_limit: 0,
+            flash_loan_fee: 9,
+            flash_loan_amount: 0,
+            deposit_reward_pool: Pubkey::default(),
+            borrow_reward_pool: Pubkey::default(),
+            unrecovered_bad_debt: 0,
+            liquidity: deposited,
+        }
+    }
+
+    #[test]
+    fn socialize_loss_lowers_the_supply_index() {
+        let mut bank = bank(1_000);
+
+        assert_eq!(bank.socialize_loss(250).unwrap(), 250);
+        assert_eq!(bank.cumulative_supply_index, WAD / 4 * 3);
+        assert_eq!(bank.total_deposited, 750);
+    }
+
+    #[test]
+    fn socialize_loss_keeps_the_index_above_the_floor() {
+        let mut bank = bank(1_000);
+
+        // la perdida supera lo depositado, solo se socializa lo que hay
+        assert_eq!(bank.socialize_loss(5_000).unwrap(), 1_000);
+        assert_eq!(bank.cumulative_supply_index, MIN_SUPPLY_INDEX);
+        assert_eq!(bank.total_deposited, 0);
+
+        // el bank sigue aceptando depositos
+        assert_eq!(
+            bank.amount_to_deposit_shares(1, Rounding::Down).unwrap(),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn reset_depleted_insurance_starts_a_new_epoch() {
+        let mut bank = bank(0);
+        bank.total_insurance_shares = 100;
+
+        bank.reset_depleted_insurance(1);
+        assert_eq!(bank.total_insurance_shares, 100);
+        assert_eq!(bank.insurance_epoch, 0);
+
+        bank.reset_depleted_insurance(0);
+        assert_eq!(bank.total_insurance_shares, 0);
+        assert_eq!(bank.insurance_epoch, 1);
+    }
+
+    #[test]
+    fn apply_config_only_changes_the_queued_params() {
+        let mut queued = bank(0);
+        queued.apply_config(&BankConfigParams {
+            reserve_factor: Some(2_000),
+            borrow_limit: Some(1_000),
+            ..Default::default()
+        });
+
+        assert_eq!(queued.reserve_factor, 2_000);
+        assert_eq!(queued.borrow_limit, 1_000);
+        assert_eq!(queued.deposit_limit, 0);
+        assert_eq!(queued.max_ltv, 7_500);
+        assert!(queued.validate_config().is_ok());
+
+        // tasas e insurance se validan igual que los parametros de riesgo
+        let mut invalid = bank(0);
+        invalid.apply_config(&BankConfigParams {
+            reserve_factor: Some(BPS_DENOMINATOR),
+            ..Default::default()
+        });
+        assert!(invalid.validate_config().is_err());
+
+        let mut invalid = bank(0);
+        invalid.apply_config(&BankConfigParams {
+            insurance_unstake_window: Some(0),
+            ..Default::default()
+        });
+        assert!(invalid.validate_config().is_err());
+    }
+
+    #[test]
+    fn interest_without_depositors_goes_to_protocol_fees() {
+        // deuda contra liquidez enviada directo al treasury, sin depositantes
+        let mut bank = bank(0);
+        bank.total_borrowed = 1_000_000;
+        bank.total_borrow_shares = 1_000_000;
+        bank.liquidity = 3_000_000;
+
+        bank.accrue_interest(crate::SECONDS_PER_YEAR as i64)
+            .unwrap();
+
+        let interest = bank.total_borrowed - 1_000_000;
+        assert!(interest > 0);
+        assert_eq!(
+            bank.accumulated_protocol_fees + bank.accumulated_insurance_fees,
+            interest
+        );
+        assert_eq!(bank.cumulative_supply_index, WAD);
+        assert_eq!(bank.total_deposited, 0);
+    }
+
+    #[test]
+    fn sync_liquidity_excludes_pending_fees() {
+        let mut bank = bank(0);
+        bank.accumulated_protocol_fees = 100;
+        bank.accumulated_insurance_fees = 50;
+
+        bank.sync_liquidity(1_000);
+        assert_eq!(bank.liquidity, 850);
+
+        bank.sync_liquidity(120);
+        assert_eq!(bank.liquidity, 0);
+    }
+
+    #[test]
+    fn socialize_loss_without_depositors_socializes_nothing() {
+        let mut bank = bank(0);
+
+        assert_eq!(bank.socialize_loss(500).unwrap(), 0);
+        assert_eq!(bank.cumulative_supply_index, WAD);
+    }
 }
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::DappError;
+
+// stake de un usuario en el insurance fund de un bank
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceStake {
+    pub owner: Pubkey,
+    pub bank: Pubkey,
+    pub shares: u64, // incluye las shares pendientes de unstake, siguen expuestas al slashing
+    pub pending_unstake_shares: u64,
+    pub unstake_requested_at: i64,
+    pub epoch: u64, // Bank.insurance_epoch en el que se emitieron las shares
+}
+
+impl InsuranceStake {
+    // las shares de un epoch anterior quedaron en cero con el slashing que vacio el fondo
+    pub fn sync_epoch(&mut self, bank_epoch: u64) {
+        if self.epoch != bank_epoch {
+            self.shares = 0;
+            self.pending_unstake_shares = 0;
+            self.unstake_requested_at = 0;
+            self.epoch = bank_epoch;
+        }
+    }
+
+    // el unstake se ejecuta entre el fin del cooldown y el fin de la ventana, despues hay que
+    // pedirlo de nuevo para que no quede una salida siempre lista ante un bad debt
+    pub fn check_unstake_window(&self, now: i64, cooldown: i64, window: i64) -> Result<()> {
+        let ready_at = self.unstake_requested_at.saturating_add(cooldown);
+        require!(now >= ready_at, DappError::UnstakeCooldownActive);
+        require!(
+            now <= ready_at.saturating_add(window),
+            DappError::UnstakeWindowExpired
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stake() -> InsuranceStake {
+        InsuranceStake {
+            owner: Pubkey::default(),
+            bank: Pubkey::default(),
+            shares: 100,
+            pending_unstake_shares: 100,
+            unstake_requested_at: 1_000,
+            epoch: 0,
+        }
+    }
+
+    #[test]
+    fn sync_epoch_discards_shares_from_a_depleted_fund() {
+        let mut stake = stake();
+
+        stake.sync_epoch(0);
+        assert_eq!(stake.shares, 100);
+
+        stake.sync_epoch(1);
+        assert_eq!(stake.shares, 0);
+        assert_eq!(stake.pending_unstake_shares, 0);
+        assert_eq!(stake.epoch, 1);
+    }
+
+    #[test]
+    fn unstake_only_inside_the_window() {
+        let stake = stake();
+
+        assert!(stake.check_unstake_window(1_099, 100, 50).is_err());
+        assert!(stake.check_unstake_window(1_100, 100, 50).is_ok());
+        assert!(stake.check_unstake_window(1_150, 100, 50).is_ok());
+        assert_eq!(
+            stake.check_unstake_window(1_151, 100, 50).unwrap_err(),
+            DappError::UnstakeWindowExpired.into()
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::{DappError, Rounding, Wad, BPS_DENOMINATOR, MAX_BORROW_RATE};
+
+// modelo de tasas con quiebre en la utilizacion optima, todo en basis points anuales
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct InterestRateModel {
+    pub base_rate: u64,           // tasa con utilizacion 0
+    pub optimal_utilization: u64, // punto de quiebre de la curva
+    pub slope1: u64,              // incremento de la tasa entre 0 y la utilizacion optima
+    pub slope2: u64,              // incremento de la tasa entre la utilizacion optima y 100%
+}
+
+impl InterestRateModel {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.optimal_utilization > 0 && self.optimal_utilization <= BPS_DENOMINATOR,
+            DappError::InvalidInterestRateModel
+        );
+        let max_rate = self
+            .base_rate
+            .checked_add(self.slope1)
+            .and_then(|rate| rate.checked_add(self.slope2));
+        require!(
+            max_rate.is_some_and(|rate| rate <= MAX_BORROW_RATE),
+            DappError::InvalidInterestRateModel
+        );
+        Ok(())
+    }
+
+    /// Annual borrow rate for the given utilization.
+    pub fn borrow_rate(&self, utilization: Wad) -> Result<Wad> {
+        let base_rate = Wad::from_bps(self.base_rate);
+        let optimal = Wad::from_bps(self.optimal_utilization);
+        let slope1 = Wad::from_bps(self.slope1);
+
+        if utilization <= optimal {
+            return base_rate.checked_add(
+                slope1
+                    .checked_mul(utilization, Rounding::Down)?
+                    .checked_div(optimal, Rounding::Down)?,
+            );
+        }
+
+        // utilization > optimal implica optimal < 100%
+        let excess = utilization
+            .checked_sub(optimal)?
+            .checked_div(Wad::ONE.checked_sub(optimal)?, Rounding::Down)?;
+
+        base_rate
+            .checked_add(slope1)?
+            .checked_add(Wad::from_bps(self.slope2).checked_mul(excess, Rounding::Down)?)
+    }
+
+    /// Annual supply rate: borrow rate × utilization × (1 − reserve factor).
+    pub fn supply_rate(&self, utilization: Wad, reserve_factor: u64) -> Result<Wad> {
+        self.borrow_rate(utilization)?
+            .checked_mul(utilization, Rounding::Down)?
+            .checked_mul(
+                Wad::ONE.checked_sub(Wad::from_bps(reserve_factor))?,
+                Rounding::Down,
+            )
+    }
+}
+
+// total_borrowed / (total_borrowed + liquidez del treasury). no depende de total_deposited,
+// asi la deuda contra tokens enviados directo al treasury tambien paga interes
+pub fn utilization(total_borrowed: u64, liquidity: u64) -> Result<Wad> {
+    if total_borrowed == 0 {
+        return Ok(Wad::ZERO);
+    }
+
+    Wad::from_ratio(
+        total_borrowed as u128,
+        total_borrowed as u128 + liquidity as u128,
+        Rounding::Down,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(base_rate: u64, slope1: u64, slope2: u64) -> InterestRateModel {
+        InterestRateModel {
+            base_rate,
+            optimal_utilization: 8_000,
+            slope1,
+            slope2,
+        }
+    }
+
+    #[test]
+    fn validate_bounds_max_borrow_rate() {
+        assert!(model(0, 400, 7_500).validate().is_ok());
+        assert!(model(0, 0, MAX_BORROW_RATE).validate().is_ok());
+        assert!(model(1, 0, MAX_BORROW_RATE).validate().is_err());
+        assert!(model(u64::MAX, 1, 0).validate().is_err());
+    }
+
+    #[test]
+    fn utilization_uses_the_treasury_liquidity() {
+        assert_eq!(utilization(0, 0).unwrap(), Wad::ZERO);
+        assert_eq!(utilization(1_000, 3_000).unwrap(), Wad::from_bps(2_500));
+        // sin liquidez todo lo prestable esta prestado
+        assert_eq!(utilization(1_000, 0).unwrap(), Wad::ONE);
+    }
+
+    #[test]
+    fn max_borrow_rate_compounds_without_overflow() {
+        // un bank a la tasa maxima sigue acumulando tras un año sin actividad
+        let rate = model(0, 0, MAX_BORROW_RATE).borrow_rate(Wad::ONE).unwrap();
+        assert_eq!(rate, Wad::from_bps(MAX_BORROW_RATE));
+        assert!(crate::compound_factor(rate, crate::SECONDS_PER_YEAR).is_ok());
+    }
+}
@@ -1,5 +1,17 @@
 pub mod bank;
+pub mod insurance;
+pub mod interest_rate;
+pub mod oracle;
+pub mod pending_config;
+pub mod protocol;
+pub mod reward;
 pub mod user;
 
 pub use bank::*;
+pub use insurance::*;
+pub use interest_rate::*;
+pub use oracle::*;
+pub use pending_config::*;
+pub use protocol::*;
+pub use reward::*;
 pub use user::*;
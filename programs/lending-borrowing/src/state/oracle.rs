@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{DappError, BPS_DENOMINATOR};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum OracleType {
+    PythPull,        // PriceUpdateV2 del pyth solana receiver
+    SwitchboardPull, // PullFeedAccountData de switchboard on-demand
+}
+
+// oracle secundario, se usa cuando el principal esta desactualizado o su conf es muy amplia
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct FallbackOracleConfig {
+    pub oracle_type: OracleType,
+    pub feed: Pubkey,        // cuenta del pull feed
+    pub max_divergence: u64, // diferencia maxima entre ambos precios en basis points
+}
+
+// configuracion del oracle de cada bank
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct OracleConfig {
+    pub feed_id: [u8; 32], // See https://pyth.network/developers/price-feed-ids for all available IDs.
+    pub oracle_type: OracleType,
+    pub max_staleness: u64,  // antiguedad maxima del precio en segundos
+    pub max_confidence: u64, // conf / price maximo en basis points
+    pub fallback: Option<FallbackOracleConfig>,
+}
+
+impl OracleConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.feed_id != [0; 32]
+                && self.oracle_type == OracleType::PythPull
+                && self.max_staleness > 0
+                && self.max_confidence > 0
+                && self.max_confidence <= BPS_DENOMINATOR,
+            DappError::InvalidOracleConfig
+        );
+
+        if let Some(fallback) = self.fallback {
+            require!(
+                fallback.oracle_type == OracleType::SwitchboardPull
+                    && fallback.feed != Pubkey::default()
+                    && fallback.max_divergence > 0
+                    && fallback.max_divergence <= BPS_DENOMINATOR,
+                DappError::InvalidOracleConfig
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::BankConfigParams;
+
+// cambio de parametros de riesgo en espera del timelock, seeds [b"pending_config", bank]
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfigChange {
+    pub bank: Pubkey,
+    pub params: BankConfigParams,
+    pub queued_at: i64,
+    pub executable_at: i64,
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+// configuracion global, unica cuenta con seeds [b"protocol"]
+#[account]
+#[derive(InitSpace)]
+pub struct Protocol {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey, // Pubkey::default() si no hay transferencia en curso
+    pub guardian: Pubkey,
+    pub fee_receiver: Pubkey,
+    pub paused: u8,                // bits PAUSE_*
+    pub config_delay: i64,         // segundos minimos entre queue y execute de un cambio de config
+    pub forbidden_extensions: u64, // bit n = ExtensionType n de token-2022 no permitido en banks nuevos
+}
+
+impl Protocol {
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+}
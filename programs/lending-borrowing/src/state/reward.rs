@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+
+use crate::{DappError, Rounding, Wad};
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum RewardSide {
+    #[default]
+    Deposit,
+    Borrow,
+}
+
+// rewards de liquidity mining para un lado de un bank, se reparten por share
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub bank: Pubkey,
+    pub side: RewardSide,
+    pub reward_mint: Pubkey,
+    pub emission_rate: u64, // tokens de reward por segundo para todo el lado del bank
+    pub start_time: i64,
+    pub end_time: i64,
+    pub last_updated: i64,
+    pub acc_reward_per_share: u128, // WAD, rewards acumulados por share desde start_time
+    pub total_allocated: u64,       // emision total de todos los periodos
+    pub total_claimed: u64,
+}
+
+impl RewardPool {
+    // acumula las emisiones entre last_updated y `now`, acotado a [start_time, end_time]
+    pub fn update(&mut self, now: i64, total_shares: u64) -> Result<()> {
+        let now = now.clamp(self.start_time, self.end_time);
+        if now <= self.last_updated {
+            return Ok(());
+        }
+
+        // sin shares las emisiones del periodo no se reparten
+        if total_shares > 0 {
+            let emitted = (self.emission_rate as u128)
+                .checked_mul((now - self.last_updated) as u128)
+                .ok_or(DappError::MathOverflow)?;
+            let delta = Wad::from_ratio(emitted, total_shares as u128, Rounding::Down)?;
+            self.acc_reward_per_share = Wad(self.acc_reward_per_share).checked_add(delta)?.0;
+        }
+
+        self.last_updated = now;
+
+        Ok(())
+    }
+
+    // lo que el vault todavia tiene que poder pagar
+    pub fn outstanding_rewards(&self) -> u64 {
+        self.total_allocated.saturating_sub(self.total_claimed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(emission_rate: u64, start_time: i64, end_time: i64) -> RewardPool {
+        RewardPool {
+            bank: Pubkey::default(),
+            side: RewardSide::Deposit,
+            reward_mint: Pubkey::default(),
+            emission_rate,
+            start_time,
+            end_time,
+            last_updated: start_time,
+            acc_reward_per_share: 0,
+            total_allocated: 0,
+            total_claimed: 0,
+        }
+    }
+
+    #[test]
+    fn update_only_emits_inside_the_period() {
+        let mut pool = pool(10, 100, 200);
+
+        pool.update(50, 1_000).unwrap();
+        assert_eq!(pool.acc_reward_per_share, 0);
+
+        // 10/s * 100s / 1000 shares = 1 por share, lo que pase de end_time no cuenta
+        pool.update(500, 1_000).unwrap();
+        assert_eq!(pool.acc_reward_per_share, Wad::ONE.0);
+        assert_eq!(pool.last_updated, 200);
+    }
+
+    #[test]
+    fn new_period_keeps_accumulating_the_index() {
+        let mut pool = pool(10, 100, 200);
+        pool.update(200, 1_000).unwrap();
+
+        // igual que create_reward_pool sobre un pool existente
+        pool.emission_rate = 20;
+        pool.start_time = 300;
+        pool.end_time = 400;
+        pool.last_updated = 300;
+        pool.update(350, 1_000).unwrap();
+
+        assert_eq!(pool.acc_reward_per_share, 2 * Wad::ONE.0);
+    }
+
+    #[test]
+    fn outstanding_rewards_discounts_claims() {
+        let mut pool = pool(10, 100, 200);
+        pool.total_allocated = 1_000;
+        pool.total_claimed = 400;
+        assert_eq!(pool.outstanding_rewards(), 600);
+    }
+}
@@ -1,18 +1,109 @@
 use anchor_lang::prelude::*;
 
+use crate::{DappError, RewardSide, Rounding, Wad, MAX_POSITIONS};
+
 #[account]
 #[derive(InitSpace)]
 pub struct User {
     pub owner: Pubkey,
-    pub deposited_sol: u64,
-    pub deposited_sol_shares: u64, // shares -> lo que vamos a usar para el calculo de interes
-    pub borrowed_sol: u64,
-    pub borrowed_sol_shares: u64,
-    pub deposited_usdc: u64,
-    pub deposited_usdc_shares: u64, // shares -> lo que vamos a usar para el calculo de interes
-    pub borrowed_usdc: u64,
-    pub borrowed_usdc_shares: u64,
-    pub usdc_address: Pubkey,
-    pub last_updated: i64,
-    pub last_updated_borrowed: i64,
+    #[max_len(MAX_POSITIONS)]
+    pub positions: Vec<Position>, // una entrada por bank en el que el usuario deposito o pidio prestado
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct Position {
+    pub bank: Pubkey,
+    pub deposit_shares: u64, // shares -> lo que vamos a usar para el calculo de interes
+    pub borrow_shares: u64,
+    pub deposit_reward_index: u128, // acc_reward_per_share del reward pool en el ultimo settle
+    pub borrow_reward_index: u128,
+    pub unclaimed_deposit_rewards: u64,
+    pub unclaimed_borrow_rewards: u64,
+}
+
+impl Position {
+    // con rewards sin cobrar la posicion se mantiene hasta el claim
+    pub fn is_empty(&self) -> bool {
+        self.deposit_shares == 0
+            && self.borrow_shares == 0
+            && self.unclaimed_deposit_rewards == 0
+            && self.unclaimed_borrow_rewards == 0
+    }
+
+    // acredita los rewards de las shares actuales hasta `acc_reward_per_share`,
+    // se llama antes de cambiar las shares de ese lado
+    pub fn settle_rewards(&mut self, side: RewardSide, acc_reward_per_share: u128) -> Result<()> {
+        let (shares, index, unclaimed) = match side {
+            RewardSide::Deposit => (
+                self.deposit_shares,
+                &mut self.deposit_reward_index,
+                &mut self.unclaimed_deposit_rewards,
+            ),
+            RewardSide::Borrow => (
+                self.borrow_shares,
+                &mut self.borrow_reward_index,
+                &mut self.unclaimed_borrow_rewards,
+            ),
+        };
+
+        let earned = Wad(acc_reward_per_share)
+            .checked_sub(Wad(*index))?
+            .checked_mul(Wad::from_u64(shares), Rounding::Down)?
+            .to_u64(Rounding::Down)?;
+        *unclaimed = unclaimed
+            .checked_add(earned)
+            .ok_or(DappError::MathOverflow)?;
+        *index = acc_reward_per_share;
+
+        Ok(())
+    }
+}
+
+impl User {
+    pub fn position(&self, bank: &Pubkey) -> Option<&Position> {
+        self.positions
+            .iter()
+            .find(|position| position.bank == *bank)
+    }
+
+    pub fn position_mut(&mut self, bank: &Pubkey) -> Result<&mut Position> {
+        self.positions
+            .iter_mut()
+            .find(|position| position.bank == *bank)
+            .ok_or(error!(DappError::PositionNotFound))
+    }
+
+    pub fn get_or_create_position(&mut self, bank: &Pubkey) -> Result<&mut Position> {
+        match self
+            .positions
+            .iter()
+            .position(|position| position.bank == *bank)
+        {
+            Some(index) => Ok(&mut self.positions[index]),
+            None => {
+                require!(
+                    self.positions.len() < MAX_POSITIONS,
+                    DappError::TooManyPositions
+                );
+                self.positions.push(Position {
+                    bank: *bank,
+                    ..Position::default()
+                });
+                Ok(self.positions.last_mut().unwrap())
+            }
+        }
+    }
+
+    pub fn has_debt(&self) -> bool {
+        self.positions
+            .iter()
+            .any(|position| position.borrow_shares > 0)
+    }
+
+    // libera el lugar de las posiciones que quedaron en cero
+    pub fn remove_empty_positions(&mut self) {
+        self.positions.retain(|position| !position.is_empty());
+    }
 }
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::Price;
+
+use crate::{mul_div_u128, DappError, Rounding, Wad, WAD};
+
+// todos los valores se expresan en USD como Wad, independientemente de los decimals
+// del mint y del exponente del precio, para poder comparar tokens distintos
+
+// 10^exponent
+fn pow10(exponent: u32) -> Result<u128> {
+    10u128
+        .checked_pow(exponent)
+        .ok_or(error!(DappError::MathOverflow))
+}
+
+// price * 10^exponent, precio de una unidad entera del token
+pub fn price_to_wad(price: &Price, rounding: Rounding) -> Result<Wad> {
+    require!(price.price > 0, DappError::InvalidPrice);
+
+    let scale = pow10(price.exponent.unsigned_abs())?;
+
+    if price.exponent < 0 {
+        Wad::from_ratio(price.price as u128, scale, rounding)
+    } else {
+        Wad::from_ratio(
+            (price.price as u128)
+                .checked_mul(scale)
+                .ok_or(DappError::MathOverflow)?,
+            1,
+            rounding,
+        )
+    }
+}
+
+/// USD value of `amount` base units of a mint with `decimals`:
+/// amount / 10^decimals * price * 10^exponent.
+pub fn token_value(amount: u64, decimals: u8, price: &Price, rounding: Rounding) -> Result<Wad> {
+    let units = Wad::from_ratio(amount as u128, pow10(decimals as u32)?, rounding)?;
+    units.checked_mul(price_to_wad(price, rounding)?, rounding)
+}
+
+/// Base units of a mint with `decimals` worth `value` USD.
+pub fn value_to_amount(value: Wad, decimals: u8, price: &Price, rounding: Rounding) -> Result<u64> {
+    // redondear el precio al reves para no sobreestimar/subestimar el amount
+    let price_rounding = match rounding {
+        Rounding::Down => Rounding::Up,
+        Rounding::Up => Rounding::Down,
+    };
+
+    let units = value.checked_div(price_to_wad(price, price_rounding)?, rounding)?;
+    let amount = mul_div_u128(units.0, pow10(decimals as u32)?, WAD, rounding)?;
+    u64::try_from(amount).map_err(|_| error!(DappError::MathOverflow))
+}
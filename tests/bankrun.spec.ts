@@ -1,209 +1,1011 @@
-import { BN, Program } from "@coral-xyz/anchor";
-import { Connection, PublicKey } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createAccount, mintTo } from "@solana/spl-token";
+import { BN, Program, Wallet } from "@coral-xyz/anchor";
+import {
+  AccountMeta,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAccount,
+  createAssociatedTokenAccountInstruction,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  mintTo,
+} from "@solana/spl-token";
 
 import { BankrunContextWrapper } from "../bankrun-utils/bankrunConnection";
 import { BankrunProvider } from "anchor-bankrun";
 import IDL from "../target/idl/lending_borrowing.json";
 import { LendingBorrowing } from "../target/types/lending_borrowing";
-import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 import { createMint } from "spl-token-bankrun";
-import { startAnchor } from "solana-bankrun";
+import { mockPythPriceUpdate, mockSwitchboardPullFeed } from "./oracle-mocks";
+import { Clock, start } from "solana-bankrun";
+import { BPF_LOADER_UPGRADEABLE_ID, upgradeableProgramAccounts } from "./upgradeable-program";
+import { expect } from "chai";
 
 describe("Lending & Borrowing Program", async () => {
-  const pyth = new PublicKey("7UVimffxr9o0w1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE"); // [?]
-  const devnetConnection = new Connection("https://api.devnet.solana.com");
-  const accountInfo = await devnetConnection.getAccountInfo(pyth);
+  //   const SOL_PRICE_FEED_ID = "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE";
+  const SOL_PRICE_FEED_ID = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+  //   const USDC_PRICE_FEED_ID = "Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX";
+  const USDC_PRICE_FEED_ID = "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
 
-  const context = await startAnchor(
-    "",
+  const solUsdPriceFeedAccount = Keypair.generate().publicKey;
+  const usdcUsdPriceFeedAccount = Keypair.generate().publicKey;
+  const solUsdSwitchboardFeed = Keypair.generate().publicKey;
+
+  // upgrade authority del programa, es quien puede inicializar el protocol
+  const admin = Keypair.generate();
+
+  const context = await start(
+    [],
     [
+      ...upgradeableProgramAccounts(
+        new PublicKey(IDL.address),
+        "target/deploy/lending_borrowing.so",
+        admin.publicKey
+      ),
       {
-        name: "lending_borrowing",
-        programId: new PublicKey(IDL.address),
+        address: admin.publicKey,
+        info: {
+          lamports: 1_000 * LAMPORTS_PER_SOL,
+          data: Buffer.alloc(0),
+          owner: SystemProgram.programId,
+          executable: false,
+        },
       },
-    ],
-    [{ address: pyth, info: accountInfo }]
+    ]
   );
 
-  const provider = new BankrunProvider(context);
+  const provider = new BankrunProvider(context, new Wallet(admin));
 
   const bankrunContextWrapper = new BankrunContextWrapper(context);
   const bankrunConnection = bankrunContextWrapper.connection.toConnection();
 
-  //   const SOL_PRICE_FEED_ID = "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE";
-  const SOL_PRICE_FEED_ID = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
-  //   const USDC_PRICE_FEED_ID = "Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX";
-  const USDC_PRICE_FEED_ID = "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
+  const bankClient = context.banksClient;
+  const signer = provider.wallet.payer;
 
-  const pythSolanaReceiver = new PythSolanaReceiver({
-    connection: bankrunConnection,
-    wallet: provider.wallet,
-  });
+  // precios mock con exponente -8: SOL $150 y USDC $1. el switchboard de SOL
+  // sigue al precio de pyth para no superar max_divergence
+  const SOL_PRICE = 15_000_000_000n;
+  const SOL_CONF = 7_500_000n; // 0.05%
+  const USDC_PRICE = 100_000_000n;
+  const USDC_CONF = 10_000n; // 0.01%
+  let prices = { sol: SOL_PRICE, solConf: SOL_CONF, usdcConf: USDC_CONF };
+
+  const setPrices = async (update: Partial<typeof prices> = {}) => {
+    prices = { ...prices, ...update };
+    const clock = await bankClient.getClock();
+    context.setAccount(
+      solUsdPriceFeedAccount,
+      mockPythPriceUpdate(SOL_PRICE_FEED_ID, prices.sol, prices.solConf, -8, clock.unixTimestamp)
+    );
+    context.setAccount(
+      usdcUsdPriceFeedAccount,
+      mockPythPriceUpdate(USDC_PRICE_FEED_ID, USDC_PRICE, prices.usdcConf, -8, clock.unixTimestamp)
+    );
+    context.setAccount(
+      solUsdSwitchboardFeed,
+      mockSwitchboardPullFeed(
+        prices.sol * 10_000_000_000n,
+        prices.solConf * 10_000_000_000n,
+        clock.unixTimestamp,
+        clock.slot
+      )
+    );
+  };
+  await setPrices();
+
+  // avanza el reloj (y un slot, para no repetir blockhash) y refresca los precios
+  const warp = async (seconds: number) => {
+    const clock = await bankClient.getClock();
+    const slot = clock.slot + 1n;
+    context.warpToSlot(slot);
+    context.setClock(
+      new Clock(
+        slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        clock.unixTimestamp + BigInt(seconds)
+      )
+    );
+    await setPrices();
+  };
 
-  const solUsdPriceFeedAccount = pythSolanaReceiver.getPriceFeedAccountAddress(
-    0,
-    SOL_PRICE_FEED_ID
-  );
+  const now = async () => Number((await bankClient.getClock()).unixTimestamp);
 
-  const usdcUsdPriceFeedAccount = pythSolanaReceiver.getPriceFeedAccountAddress(
-    0,
-    USDC_PRICE_FEED_ID
-  );
+  const program = new Program<LendingBorrowing>(IDL as LendingBorrowing, provider);
 
-  context.setAccount(solUsdPriceFeedAccount, accountInfo);
-  context.setAccount(usdcUsdPriceFeedAccount, accountInfo);
+  const oracleConfig = (feedId: string, fallbackFeed: PublicKey | null = null) => ({
+    feedId: Array.from(Buffer.from(feedId, "hex")),
+    oracleType: { pythPull: {} },
+    maxStaleness: bn(30),
+    maxConfidence: bn(200),
+    fallback: fallbackFeed && {
+      oracleType: { switchboardPull: {} },
+      feed: fallbackFeed,
+      maxDivergence: bn(100),
+    },
+  });
 
-  const program = new Program<LendingBorrowing>(IDL as LendingBorrowing, provider);
+  const fundedKeypair = () => {
+    const keypair = Keypair.generate();
+    context.setAccount(keypair.publicKey, {
+      lamports: 10 * LAMPORTS_PER_SOL,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    });
+    return keypair;
+  };
 
-  const bankClient = context.banksClient;
-  const signer = provider.wallet.payer;
+  const tokenBalance = async (address: PublicKey) =>
+    (await bankrunContextWrapper.connection.getTokenAccount(address)).amount;
 
   const mintUSDC = await createMint(bankrunConnection, signer, signer.publicKey, null, 9);
   const mintSOL = await createMint(bankrunConnection, signer, signer.publicKey, null, 9);
 
-  const [bankUsdcAccount] = PublicKey.findProgramAddressSync(
-    [Buffer.from("treasury"), mintUSDC.toBuffer()],
-    program.programId
+  const pda = (...seeds: Buffer[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
   );
-  const [bankSolAccount] = PublicKey.findProgramAddressSync(
-    [Buffer.from("treasury"), mintSOL.toBuffer()],
-    program.programId
+  const protocolAccount = pda(Buffer.from("protocol"));
+  const userAccount = pda(signer.publicKey.toBuffer());
+
+  const usdcBank = pda(mintUSDC.toBuffer());
+  const solBank = pda(mintSOL.toBuffer());
+  const bankUsdcAccount = pda(Buffer.from("treasury"), mintUSDC.toBuffer());
+  const bankSolAccount = pda(Buffer.from("treasury"), mintSOL.toBuffer());
+  const usdcReceiptMint = pda(Buffer.from("receipt"), mintUSDC.toBuffer());
+  const usdcCollateralVault = pda(Buffer.from("collateral"), mintUSDC.toBuffer());
+  const usdcPendingConfig = pda(Buffer.from("pending_config"), usdcBank.toBuffer());
+  const usdcInsuranceStake = pda(
+    Buffer.from("insurance_stake"),
+    usdcBank.toBuffer(),
+    signer.publicKey.toBuffer()
   );
+  const solInsuranceVault = pda(Buffer.from("insurance"), mintSOL.toBuffer());
+  const solInsuranceStake = pda(
+    Buffer.from("insurance_stake"),
+    solBank.toBuffer(),
+    signer.publicKey.toBuffer()
+  );
+  const usdcDepositRewardPool = pda(Buffer.from("reward_pool"), usdcBank.toBuffer(), Buffer.from([0]));
+  const usdcDepositRewardVault = pda(Buffer.from("reward_vault"), usdcDepositRewardPool.toBuffer());
+
+  const userUsdcAccount = getAssociatedTokenAddressSync(mintUSDC, signer.publicKey);
+  const userSolAccount = getAssociatedTokenAddressSync(mintSOL, signer.publicKey);
+  const userReceiptAccount = getAssociatedTokenAddressSync(usdcReceiptMint, signer.publicKey);
+
+  const readonly = (pubkey: PublicKey): AccountMeta => ({
+    pubkey,
+    isSigner: false,
+    isWritable: false,
+  });
+
+  // [bank, price_update, fallback_feed] en el orden de las posiciones del usuario: USDC, SOL.
+  // USDC no tiene fallback
+  const healthAccounts = [
+    readonly(usdcBank),
+    readonly(usdcUsdPriceFeedAccount),
+    readonly(usdcUsdPriceFeedAccount),
+    readonly(solBank),
+    readonly(solUsdPriceFeedAccount),
+    readonly(solUsdSwitchboardFeed),
+  ];
+
+  const usdcPosition = async () =>
+    (await program.account.user.fetch(userAccount)).positions.find((position) =>
+      position.bank.equals(usdcBank)
+    );
+  const solPosition = async () =>
+    (await program.account.user.fetch(userAccount)).positions.find((position) =>
+      position.bank.equals(solBank)
+    );
+
+  const deposit = (amount: number) =>
+    program.methods
+      .depositToken(bn(amount))
+      .accounts({
+        mintAccount: mintUSDC,
+        rewardPool: null,
+        priceUpdate: usdcUsdPriceFeedAccount,
+        fallbackFeed: usdcUsdPriceFeedAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  const borrow = (amount: number) =>
+    program.methods
+      .borrowToken(bn(amount))
+      .accounts({
+        mintAccount: mintSOL,
+        rewardPool: null,
+        priceUpdate: solUsdPriceFeedAccount,
+        fallbackFeed: solUsdSwitchboardFeed,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(healthAccounts)
+      .rpc();
+
+  const liquidator = fundedKeypair();
+  const liquidate = (repayAmount: number) =>
+    program.methods
+      .liquidatePosition(bn(repayAmount), bn(0))
+      .accountsPartial({
+        liquidator: liquidator.publicKey,
+        borrower: signer.publicKey,
+        collateralMint: mintUSDC,
+        borrowedMint: mintSOL,
+        collateralPriceUpdate: usdcUsdPriceFeedAccount,
+        borrowedPriceUpdate: solUsdPriceFeedAccount,
+        collateralFallbackFeed: usdcUsdPriceFeedAccount,
+        borrowedFallbackFeed: solUsdSwitchboardFeed,
+        collateralRewardPool: usdcDepositRewardPool,
+        borrowedRewardPool: null,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        borrowedTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(healthAccounts)
+      .signers([liquidator])
+      .rpc();
+
+  // BankConfigParams con todo en null (sin cambios) salvo lo indicado
+  const configParams = (params: object = {}) => ({
+    maxLtv: null,
+    liquidationThreshold: null,
+    liquidationBonus: null,
+    liquidationCloseFactor: null,
+    flashLoanFee: null,
+    rateModel: null,
+    reserveFactor: null,
+    oracle: null,
+    depositLimit: null,
+    borrowLimit: null,
+    insuranceFee: null,
+    insuranceCooldown: null,
+    insuranceUnstakeWindow: null,
+    ...params,
+  });
+
+  // encola el cambio y lo ejecuta una vez pasado el timelock
+  const applyConfig = async (bank: PublicKey, params: object) => {
+    await program.methods
+      .queueConfigChange(configParams(params) as any)
+      .accountsPartial({ bank })
+      .rpc();
+    const protocol = await program.account.protocol.fetch(protocolAccount);
+    await warp(protocol.configDelay.toNumber() + 1);
+    await program.methods
+      .executeConfigChange()
+      .accountsPartial({
+        bank,
+        pendingConfigChange: pda(Buffer.from("pending_config"), bank.toBuffer()),
+      })
+      .rpc();
+  };
+
+  it("Should reject initializing the Protocol from other than the upgrade authority", async () => {
+    const intruder = fundedKeypair();
+
+    await expectError(
+      program.methods
+        .initializeProtocol(intruder.publicKey, intruder.publicKey)
+        .accounts({ admin: intruder.publicKey, programData })
+        .signers([intruder])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Should initialize the Protocol account", async () => {
+    await program.methods
+      .initializeProtocol(signer.publicKey, signer.publicKey)
+      .accounts({ programData })
+      .rpc();
+
+    const protocol = await program.account.protocol.fetch(protocolAccount);
+    expect(protocol.admin.equals(signer.publicKey)).to.be.true;
+    expect(protocol.guardian.equals(signer.publicKey)).to.be.true;
+    expect(protocol.feeReceiver.equals(signer.publicKey)).to.be.true;
+    expect(protocol.paused).to.equal(0);
+  });
+
+  it("Should reject a config delay below the minimum", async () => {
+    const protocol = await program.account.protocol.fetch(protocolAccount);
+
+    await expectError(
+      program.methods
+        .updateProtocolConfig(signer.publicKey, signer.publicKey, bn(60), protocol.forbiddenExtensions)
+        .rpc(),
+      "InvalidConfigDelay"
+    );
+  });
 
   it("Should initialize and fund Bank accounts", async () => {
-    const initUsdcBankTx = await program.methods
-      .initializeBank(bn(1), bn(1))
+    await program.methods
+      .initializeBank(bn(8_000), bn(7_500), oracleConfig(USDC_PRICE_FEED_ID))
       .accounts({ mintAccount: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
       .rpc();
 
-    console.log("USDC Bank Account tx signature:", initUsdcBankTx);
-
-    const initSolBankTx = await program.methods
-      .initializeBank(bn(1), bn(1))
+    await program.methods
+      .initializeBank(bn(8_000), bn(7_500), oracleConfig(SOL_PRICE_FEED_ID, solUsdSwitchboardFeed))
       .accounts({ mintAccount: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
       .rpc();
 
-    console.log("SOL Bank Account tx signature:", initSolBankTx);
+    const bank = await program.account.bank.fetch(usdcBank);
+    expect(bank.mintAddress.equals(mintUSDC)).to.be.true;
+    expect(bank.liquidationThreshold.toNumber()).to.equal(8_000);
+    expect(bank.maxLtv.toNumber()).to.equal(7_500);
+    expect(bank.flashLoanFee.toNumber()).to.equal(9);
+    expect(bank.status).to.deep.equal({ active: {} });
 
+    // liquidez inicial directo al treasury, sin depositantes
     const amount = 10_000 * 10 ** 9;
+    await mintTo(bankrunConnection, signer, mintUSDC, bankUsdcAccount, signer, amount);
+    await mintTo(bankrunConnection, signer, mintSOL, bankSolAccount, signer, amount);
 
-    const usdcMintedTx = await mintTo(
-      bankrunConnection,
-      signer,
-      mintUSDC,
-      bankUsdcAccount,
-      signer,
-      amount
+    expect(await tokenBalance(bankSolAccount)).to.equal(BigInt(amount));
+  });
+
+  it("Should initialize User accounts and token accounts", async () => {
+    await program.methods.intializeUser().rpc();
+
+    await createAccount(bankrunConnection, signer, mintUSDC, signer.publicKey);
+    await mintTo(bankrunConnection, signer, mintUSDC, userUsdcAccount, signer, 2_000 * 10 ** 9);
+
+    await createAccount(bankrunConnection, signer, mintSOL, signer.publicKey);
+    await mintTo(bankrunConnection, signer, mintSOL, userSolAccount, signer, 1_000 * 10 ** 9);
+
+    const user = await program.account.user.fetch(userAccount);
+    expect(user.positions).to.be.empty;
+  });
+
+  it("Should deposit", async () => {
+    await deposit(1_000 * 10 ** 9);
+
+    expect(await tokenBalance(userUsdcAccount)).to.equal(1_000_000_000_000n);
+    expect(await tokenBalance(bankUsdcAccount)).to.equal(11_000_000_000_000n);
+    // los receipts de las shares quedan en el vault de colateral
+    expect(await tokenBalance(usdcCollateralVault)).to.equal(1_000_000_000_000n);
+
+    const bank = await program.account.bank.fetch(usdcBank);
+    expect(bank.totalDeposited.toString()).to.equal("1000000000000");
+    expect(bank.totalDepositShares.toString()).to.equal("1000000000000");
+    expect((await usdcPosition()).depositShares.toString()).to.equal("1000000000000");
+  });
+
+  it("Should block deposits while paused", async () => {
+    await program.methods.setPaused(PAUSE_DEPOSIT).rpc();
+    expect((await program.account.protocol.fetch(protocolAccount)).paused).to.equal(PAUSE_DEPOSIT);
+
+    await expectError(deposit(1 * 10 ** 9), "OperationPaused");
+
+    await program.methods.setPaused(0).rpc();
+    expect((await program.account.protocol.fetch(protocolAccount)).paused).to.equal(0);
+  });
+
+  it("Should enforce the bank status", async () => {
+    const setStatus = (status: object) =>
+      program.methods.setBankStatus(status as any).accountsPartial({ bank: usdcBank }).rpc();
+
+    await setStatus({ reduceOnly: {} });
+    await expectError(deposit(2 * 10 ** 9), "BankNotActive");
+
+    await setStatus({ frozen: {} });
+    await expectError(
+      program.methods
+        .withdrawToken(bn(3 * 10 ** 9))
+        .accounts({
+          mintAccount: mintUSDC,
+          rewardPool: null,
+          priceUpdate: usdcUsdPriceFeedAccount,
+          fallbackFeed: usdcUsdPriceFeedAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc(),
+      "BankFrozen"
     );
-    console.log("Minted USDC tx signature:", usdcMintedTx);
 
-    const solMintedTx = await mintTo(
-      bankrunConnection,
-      signer,
-      mintSOL,
-      bankSolAccount,
-      signer,
-      amount
+    await setStatus({ active: {} });
+    expect((await program.account.bank.fetch(usdcBank)).status).to.deep.equal({ active: {} });
+  });
+
+  it("Should enforce deposit and borrow limits", async () => {
+    // los limites tambien pasan por el timelock
+    await applyConfig(usdcBank, { depositLimit: bn(1_200 * 10 ** 9) });
+
+    await expectError(deposit(300 * 10 ** 9), "DepositLimitExceeded");
+    await deposit(200 * 10 ** 9);
+    expect((await program.account.bank.fetch(usdcBank)).totalDeposited.toString()).to.equal(
+      "1200000000000"
     );
-    console.log("Minted SOL tx signature:", solMintedTx);
+
+    await applyConfig(usdcBank, { depositLimit: bn(0) });
+
+    await applyConfig(solBank, { borrowLimit: bn(1 * 10 ** 9) });
+    await expectError(borrow(2 * 10 ** 9), "BorrowLimitExceeded");
+    await applyConfig(solBank, { borrowLimit: bn(0) });
   });
 
-  it("Should initialize User accounts and token accounts", async () => {
-    const initUserUsdcTx = await program.methods.intializeUser(mintUSDC).rpc();
-    console.log("User USDC Account tx signature:", initUserUsdcTx);
+  it("Should reject prices with a confidence interval too wide", async () => {
+    // USDC no tiene fallback, un conf del 3% supera max_confidence (2%)
+    await setPrices({ usdcConf: 3_000_000n });
+    await expectError(borrow(1 * 10 ** 9), "PriceConfidenceTooWide");
+    await setPrices({ usdcConf: USDC_CONF });
+  });
 
-    const initUserSolTx = await program.methods.intializeUser(mintSOL).rpc();
-    console.log("User SOL Account tx signature:", initUserSolTx);
+  it("Should value collateral and debt with the confidence-adjusted price", async () => {
+    // a precio medio 1200 USDC * 75% = $900 = 6 SOL, con conf el colateral vale
+    // $899.91 y 6 SOL de deuda $900.45
+    await expectError(borrow(6 * 10 ** 9), "OverBorrowableAmount");
+  });
 
-    const usdcTokenAccount = await createAccount(
-      bankrunConnection,
-      signer,
-      mintUSDC,
-      signer.publicKey
-    );
+  it("Should borrow", async () => {
+    await borrow(4 * 10 ** 9);
 
-    const amount = 1_000 * 10 ** 9;
+    expect(await tokenBalance(userSolAccount)).to.equal(1_004_000_000_000n);
 
-    const usdcMintedTx = await mintTo(
-      bankrunConnection,
-      signer,
-      mintUSDC,
-      usdcTokenAccount,
-      signer,
-      amount
+    // todavia no paso tiempo con deuda, el indice de borrow sigue en 1
+    const bank = await program.account.bank.fetch(solBank);
+    expect(bank.totalBorrowed.toString()).to.equal("4000000000");
+    expect(bank.totalBorrowShares.toString()).to.equal("4000000000");
+    expect((await solPosition()).borrowShares.toString()).to.equal("4000000000");
+  });
+
+  it("Should repay", async () => {
+    await program.methods
+      .repayToken(bn(1 * 10 ** 9))
+      .accounts({ mintAccount: mintSOL, rewardPool: null, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+    expect(await tokenBalance(userSolAccount)).to.equal(1_003_000_000_000n);
+    expect((await program.account.bank.fetch(solBank)).totalBorrowed.toString()).to.equal(
+      "3000000000"
     );
+    expect((await solPosition()).borrowShares.toString()).to.equal("3000000000");
+  });
 
-    console.log("Minted USDC User tx signature:", usdcMintedTx);
+  it("Should flash borrow and repay in the same transaction", async () => {
+    const balanceBefore = await tokenBalance(userSolAccount);
+    const bankBefore = await program.account.bank.fetch(solBank);
 
-    const solTokenAccount = await createAccount(
-      bankrunConnection,
-      signer,
-      mintSOL,
-      signer.publicKey
+    const flashBorrowIx = await program.methods
+      .flashBorrow(bn(1 * 10 ** 9))
+      .accounts({ mintAccount: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+      .instruction();
+
+    await program.methods
+      .flashRepay()
+      .accounts({ mintAccount: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+      .preInstructions([flashBorrowIx])
+      .rpc();
+
+    // fee 0.09% = 900_000, sin depositantes de SOL todo va al protocolo y la mitad de eso al insurance
+    expect(balanceBefore - (await tokenBalance(userSolAccount))).to.equal(900_000n);
+
+    const bank = await program.account.bank.fetch(solBank);
+    expect(bank.flashLoanAmount.toNumber()).to.equal(0);
+    expect(bank.accumulatedProtocolFees.sub(bankBefore.accumulatedProtocolFees).toNumber()).to.equal(
+      450_000
     );
+    expect(
+      bank.accumulatedInsuranceFees.sub(bankBefore.accumulatedInsuranceFees).toNumber()
+    ).to.equal(450_000);
+  });
 
-    const solMintedTx = await mintTo(
-      bankrunConnection,
-      signer,
-      mintSOL,
-      solTokenAccount,
-      signer,
-      amount
+  it("Should reject a flash borrow without a flash repay", async () => {
+    await expectError(
+      program.methods
+        .flashBorrow(bn(2 * 10 ** 9))
+        .accounts({ mintAccount: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc(),
+      "FlashRepayMissing"
     );
-    console.log("Minted SOL User tx signature:", solMintedTx);
   });
 
-  it("Should deposit", async () => {
-    const depositUSDCTx = await program.methods
-      .depositToken(bn(1000 * 10 ** 9))
+  it("Should not let the first insurance staker take the fees already in the fund", async () => {
+    // los 450_000 de insurance del flash loan no son de ningun staker, vuelven al protocolo
+    await program.methods
+      .stakeInsurance(bn(1 * 10 ** 9))
+      .accounts({ mintAccount: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+    expect(await tokenBalance(solInsuranceVault)).to.equal(1_000_000_000n);
+    expect((await program.account.insuranceStake.fetch(solInsuranceStake)).shares.toString()).to.equal(
+      "1000000000"
+    );
+
+    const bank = await program.account.bank.fetch(solBank);
+    expect(bank.totalInsuranceShares.toString()).to.equal("1000000000");
+    expect(bank.accumulatedInsuranceFees.toNumber()).to.equal(0);
+    expect(bank.accumulatedProtocolFees.toNumber()).to.equal(900_000);
+  });
+
+  it("Should withdraw", async () => {
+    await program.methods
+      .withdrawToken(bn(100 * 10 ** 9))
       .accounts({
         mintAccount: mintUSDC,
+        rewardPool: null,
+        priceUpdate: usdcUsdPriceFeedAccount,
+        fallbackFeed: usdcUsdPriceFeedAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(healthAccounts)
       .rpc();
 
-    console.log("Deposit USDC tx signature:", depositUSDCTx);
+    expect(await tokenBalance(userUsdcAccount)).to.equal(900_000_000_000n);
+    expect((await usdcPosition()).depositShares.toString()).to.equal("1100000000000");
+    expect(await tokenBalance(usdcCollateralVault)).to.equal(1_100_000_000_000n);
   });
 
-  it("Should borrow", async () => {
-    const borrowSolTx = await program.methods
-      .borrowToken(bn(20 * 10 ** 9))
-      .accounts({
-        mintAccount: mintSOL,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        priceUpdate: solUsdPriceFeedAccount,
-      })
+  it("Should unlock and lock deposit receipts", async () => {
+    await program.methods
+      .unlockCollateral(bn(100 * 10 ** 9))
+      .accounts({ mintAccount: mintUSDC, rewardPool: null, tokenProgram: TOKEN_PROGRAM_ID })
+      .remainingAccounts(healthAccounts)
       .rpc();
 
-    console.log("Borrow SOL tx signature:", borrowSolTx);
+    expect(await tokenBalance(userReceiptAccount)).to.equal(100_000_000_000n);
+    expect((await usdcPosition()).depositShares.toString()).to.equal("1000000000000");
+
+    const lock = (shares: number) =>
+      program.methods
+        .lockCollateral(bn(shares))
+        .accounts({
+          mintAccount: mintUSDC,
+          userReceiptAccount,
+          rewardPool: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    // bloquear colateral es un deposito, respeta la pausa de depositos
+    await program.methods.setPaused(PAUSE_DEPOSIT).rpc();
+    await expectError(lock(50 * 10 ** 9), "OperationPaused");
+    await program.methods.setPaused(0).rpc();
+
+    await lock(100 * 10 ** 9);
+
+    expect(await tokenBalance(userReceiptAccount)).to.equal(0n);
+    expect((await usdcPosition()).depositShares.toString()).to.equal("1100000000000");
   });
 
-  it("Should repay", async () => {
-    const repaySolTx = await program.methods
-      .repayToken(bn(20 * 10 ** 9))
-      .accounts({
-        mintAccount: mintSOL,
-        tokenProgram: TOKEN_PROGRAM_ID,
+  it("Should apply risk parameter changes only after the timelock", async () => {
+    const params = (maxLtv: number, flashLoanFee: number | null = null) =>
+      configParams({
+        maxLtv: bn(maxLtv),
+        flashLoanFee: flashLoanFee === null ? null : bn(flashLoanFee),
+      });
+    const queue = (maxLtv: number, flashLoanFee: number | null = null) =>
+      program.methods
+        .queueConfigChange(params(maxLtv, flashLoanFee) as any)
+        .accountsPartial({ bank: usdcBank })
+        .rpc();
+    const execute = () =>
+      program.methods
+        .executeConfigChange()
+        .accountsPartial({ bank: usdcBank, pendingConfigChange: usdcPendingConfig })
+        .rpc();
+
+    // max_ltv tiene que quedar por debajo del liquidation_threshold
+    await expectError(queue(9_000), "InvalidBankConfig");
+
+    await queue(7_000);
+    await expectError(execute(), "ConfigChangeNotReady");
+
+    // el guardian puede cancelar el cambio pendiente
+    await program.methods
+      .cancelConfigChange()
+      .accountsPartial({ admin: signer.publicKey, pendingConfigChange: usdcPendingConfig })
+      .rpc();
+    expect(await program.account.pendingConfigChange.fetchNullable(usdcPendingConfig)).to.be.null;
+
+    await queue(7_000, 20);
+    const protocol = await program.account.protocol.fetch(protocolAccount);
+    await warp(protocol.configDelay.toNumber() + 1);
+    await execute();
+
+    const bank = await program.account.bank.fetch(usdcBank);
+    expect(bank.maxLtv.toNumber()).to.equal(7_000);
+    expect(bank.flashLoanFee.toNumber()).to.equal(20);
+    expect(bank.liquidationThreshold.toNumber()).to.equal(8_000);
+    expect(await program.account.pendingConfigChange.fetchNullable(usdcPendingConfig)).to.be.null;
+  });
+
+  it("Should only release insurance stakes inside the window after the cooldown", async () => {
+    const balanceBefore = await tokenBalance(userUsdcAccount);
+    const stakeInsurance = () =>
+      program.methods
+        .stakeInsurance(bn(100 * 10 ** 9))
+        .accounts({ mintAccount: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+    // stakear respeta la pausa de depositos
+    await program.methods.setPaused(PAUSE_DEPOSIT).rpc();
+    await expectError(stakeInsurance(), "OperationPaused");
+    await program.methods.setPaused(0).rpc();
+
+    await stakeInsurance();
+
+    const stake = await program.account.insuranceStake.fetch(usdcInsuranceStake);
+    expect(stake.shares.toString()).to.equal("100000000000");
+
+    const requestUnstake = () =>
+      program.methods
+        .requestUnstakeInsurance(stake.shares)
+        .accountsPartial({ bank: usdcBank })
+        .rpc();
+    const unstake = () =>
+      program.methods
+        .unstakeInsurance()
+        .accounts({ mintAccount: mintUSDC, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+    await requestUnstake();
+    await expectError(unstake(), "UnstakeCooldownActive");
+
+    // pasada la ventana el pedido vence y hay que volver a hacerlo
+    const bank = await program.account.bank.fetch(usdcBank);
+    const cooldown = bank.insuranceCooldown.toNumber();
+    await warp(cooldown + bank.insuranceUnstakeWindow.toNumber() + 1);
+    await expectError(unstake(), "UnstakeWindowExpired");
+
+    await requestUnstake();
+    await warp(cooldown + 1);
+
+    // y el unstake la de retiros
+    await program.methods.setPaused(PAUSE_WITHDRAW).rpc();
+    await expectError(unstake(), "OperationPaused");
+    await program.methods.setPaused(0).rpc();
+
+    await unstake();
+
+    expect(await tokenBalance(userUsdcAccount)).to.equal(balanceBefore);
+    expect((await program.account.insuranceStake.fetch(usdcInsuranceStake)).shares.toNumber()).to.equal(
+      0
+    );
+  });
+
+  it("Should credit the amount received from Token-2022 mints with transfer fee", async () => {
+    // mint con transfer fee del 1%
+    const mintKeypair = Keypair.generate();
+    const mint2022 = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const rent = await bankClient.getRent();
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: signer.publicKey,
+          newAccountPubkey: mint2022,
+          space: mintLen,
+          lamports: Number(rent.minimumBalance(BigInt(mintLen))),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mint2022,
+          signer.publicKey,
+          signer.publicKey,
+          100,
+          BigInt(1_000_000 * 10 ** 6),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint2022, 6, signer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [mintKeypair]
+    );
+
+    await program.methods
+      .initializeBank(bn(8_000), bn(7_500), oracleConfig(USDC_PRICE_FEED_ID))
+      .accounts({ mintAccount: mint2022, tokenProgram: TOKEN_2022_PROGRAM_ID })
+      .rpc();
+
+    // otro usuario, para no sumar una posicion a la del resto de los tests
+    const feeUser = fundedKeypair();
+    const feeUserTokenAccount = getAssociatedTokenAddressSync(
+      mint2022,
+      feeUser.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          signer.publicKey,
+          feeUserTokenAccount,
+          feeUser.publicKey,
+          mint2022,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createMintToInstruction(
+          mint2022,
+          feeUserTokenAccount,
+          signer.publicKey,
+          1_000 * 10 ** 6,
+          [],
+          TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+
+    await program.methods
+      .depositToken(bn(1_000 * 10 ** 6))
+      .accountsPartial({
+        signer: feeUser.publicKey,
+        mintAccount: mint2022,
+        rewardPool: null,
+        priceUpdate: usdcUsdPriceFeedAccount,
+        fallbackFeed: usdcUsdPriceFeedAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
+      .signers([feeUser])
       .rpc();
 
-    console.log("Repay SOL tx signature:", repaySolTx);
+    // llegan 990, las shares y el total depositado se calculan sobre eso
+    const bank2022 = pda(mint2022.toBuffer());
+    const treasury2022 = pda(Buffer.from("treasury"), mint2022.toBuffer());
+    expect(await tokenBalance(treasury2022)).to.equal(990_000_000n);
+
+    const bank = await program.account.bank.fetch(bank2022);
+    expect(bank.totalDeposited.toString()).to.equal("990000000");
+    expect(bank.totalDepositShares.toString()).to.equal("990000000");
+
+    const user = await program.account.user.fetch(pda(feeUser.publicKey.toBuffer()));
+    expect(user.positions).to.have.lengthOf(1);
+    expect(user.positions[0].depositShares.toString()).to.equal("990000000");
   });
 
-  it("Should withdraw", async () => {
-    const withdrawUSDCTx = await program.methods
-      .withdrawToken(bn(500 * 10 ** 9))
+  it("Should create a funded reward pool and claim rewards", async () => {
+    const createRewardPool = (startTime: number, endTime: number, funding: number) =>
+      program.methods
+        .createRewardPool({ deposit: {} }, bn(1_000), bn(startTime), bn(endTime), bn(funding))
+        .accountsPartial({
+          bank: usdcBank,
+          rewardPool: usdcDepositRewardPool,
+          rewardVault: usdcDepositRewardVault,
+          adminRewardAccount: userSolAccount,
+          rewardMint: mintSOL,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    const start = await now();
+
+    await expectError(createRewardPool(start - 10, start + 1_000, 1_010_000), "InvalidRewardPoolConfig");
+    // 1000 por segundo durante 1000 segundos
+    await expectError(createRewardPool(start, start + 1_000, 999_999), "InsufficientRewardFunding");
+
+    const balanceBefore = await tokenBalance(userSolAccount);
+    await createRewardPool(start, start + 1_000, 1_000_000);
+
+    expect(balanceBefore - (await tokenBalance(userSolAccount))).to.equal(1_000_000n);
+    expect(await tokenBalance(usdcDepositRewardVault)).to.equal(1_000_000n);
+    expect((await program.account.bank.fetch(usdcBank)).depositRewardPool.equals(usdcDepositRewardPool)).to
+      .be.true;
+    expect((await program.account.rewardPool.fetch(usdcDepositRewardPool)).totalAllocated.toNumber()).to.equal(
+      1_000_000
+    );
+
+    // no se puede arrancar otro periodo hasta que termine el actual
+    await expectError(createRewardPool(start + 10, start + 20, 0), "RewardCampaignActive");
+
+    await warp(500);
+    const claimBefore = await tokenBalance(userSolAccount);
+    const claim = () =>
+      program.methods
+        .claimRewards({ deposit: {} })
+        .accountsPartial({
+          bank: usdcBank,
+          rewardPool: usdcDepositRewardPool,
+          rewardVault: usdcDepositRewardVault,
+          rewardMint: mintSOL,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    // cobrar rewards respeta la pausa de retiros
+    await program.methods.setPaused(PAUSE_WITHDRAW).rpc();
+    await expectError(claim(), "OperationPaused");
+    await program.methods.setPaused(0).rpc();
+
+    await claim();
+
+    // unico depositante de USDC: se lleva todo lo emitido en 500 segundos
+    const claimed = Number((await tokenBalance(userSolAccount)) - claimBefore);
+    expect(claimed).to.be.closeTo(500_000, 2);
+    expect((await program.account.rewardPool.fetch(usdcDepositRewardPool)).totalClaimed.toNumber()).to.equal(
+      claimed
+    );
+    expect((await usdcPosition()).unclaimedDepositRewards.toNumber()).to.equal(0);
+  });
+
+  it("Should not liquidate a healthy position", async () => {
+    await expectError(liquidate(1 * 10 ** 9), "LiquidationThresholdIsTooLow");
+  });
+
+  it("Should reject liquidating with the same collateral and borrowed mint", async () => {
+    // ambos banks serian la misma cuenta y se perderian los cambios del colateral
+    await expectError(
+      program.methods
+        .liquidatePosition(bn(1 * 10 ** 9), bn(0))
+        .accountsPartial({
+          liquidator: liquidator.publicKey,
+          borrower: signer.publicKey,
+          collateralMint: mintUSDC,
+          borrowedMint: mintUSDC,
+          collateralPriceUpdate: usdcUsdPriceFeedAccount,
+          borrowedPriceUpdate: usdcUsdPriceFeedAccount,
+          collateralFallbackFeed: usdcUsdPriceFeedAccount,
+          borrowedFallbackFeed: usdcUsdPriceFeedAccount,
+          collateralRewardPool: usdcDepositRewardPool,
+          borrowedRewardPool: null,
+          collateralTokenProgram: TOKEN_PROGRAM_ID,
+          borrowedTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(healthAccounts)
+        .signers([liquidator])
+        .rpc(),
+      "SameLiquidationBank"
+    );
+  });
+
+  it("Should liquidate an unhealthy position", async () => {
+    const liquidatorSolAccount = await createAccount(
+      bankrunConnection,
+      signer,
+      mintSOL,
+      liquidator.publicKey
+    );
+    await mintTo(bankrunConnection, signer, mintSOL, liquidatorSolAccount, signer, 10 * 10 ** 9);
+    const liquidatorUsdcAccount = getAssociatedTokenAddressSync(mintUSDC, liquidator.publicKey);
+
+    // 3 SOL a $300 = $900 de deuda > 1100 USDC * 80%
+    await warp(100);
+    await setPrices({ sol: 30_000_000_000n });
+
+    await liquidate(1 * 10 ** 9);
+
+    // 1 SOL * $300 * 1.05 de bonus = 315 USDC
+    expect(await tokenBalance(liquidatorSolAccount)).to.equal(9_000_000_000n);
+    expect(await tokenBalance(liquidatorUsdcAccount)).to.equal(315_000_000_000n);
+
+    // la deuda contra la liquidez del treasury acumulo interes en los warps anteriores,
+    // el SOL repagado cancela menos de 1 SOL en shares
+    const solBankAfter = await program.account.bank.fetch(solBank);
+    expect(solBankAfter.cumulativeBorrowIndex.gt(new BN(WAD.toString()))).to.be.true;
+    expect((await solPosition()).borrowShares.toString()).to.equal(
+      String(3_000_000_000n - repaidShares(1_000_000_000n, solBankAfter.cumulativeBorrowIndex))
+    );
+
+    const position = await usdcPosition();
+    expect(position.depositShares.toString()).to.equal("785000000000");
+    // los rewards se acreditaron con las shares previas a la liquidacion
+    expect(position.unclaimedDepositRewards.toNumber()).to.be.closeTo(100_000, 2);
+  });
+
+  it("Should settle bad debt once the borrower only has dust collateral left", async () => {
+    const settleBadDebt = () =>
+      program.methods
+        .settleBadDebt()
+        .accountsPartial({
+          borrower: signer.publicKey,
+          mintAccount: mintSOL,
+          rewardPool: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(healthAccounts)
+        .rpc();
+
+    await expectError(settleBadDebt(), "BorrowerHasCollateral");
+
+    // a $1000 la deuda supera al colateral: se liquida todo y el repago se reduce en proporcion
+    await setPrices({ sol: 100_000_000_000n });
+    await warp(1);
+    const sharesBefore = BigInt((await solPosition()).borrowShares.toString());
+    await liquidate(1 * 10 ** 9);
+
+    const liquidatorUsdcAccount = getAssociatedTokenAddressSync(mintUSDC, liquidator.publicKey);
+    expect(await tokenBalance(liquidatorUsdcAccount)).to.equal(1_100_000_000_000n);
+    expect((await usdcPosition()).depositShares.toNumber()).to.equal(0);
+    // 1 SOL * 785 / 1050
+    const borrowIndex = (await program.account.bank.fetch(solBank)).cumulativeBorrowIndex;
+    const remainingShares = sharesBefore - repaidShares(747_619_048n, borrowIndex);
+    expect((await solPosition()).borrowShares.toString()).to.equal(String(remainingShares));
+
+    // un deposito de polvo ($0.000001) no bloquea el settle
+    await program.methods
+      .depositToken(bn(1_000))
       .accounts({
         mintAccount: mintUSDC,
+        rewardPool: usdcDepositRewardPool,
+        priceUpdate: usdcUsdPriceFeedAccount,
+        fallbackFeed: usdcUsdPriceFeedAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
-    console.log("Withdraw USDC tx signature:", withdrawUSDCTx);
+    // el settle es en el mismo instante, la deuda no cambia
+    const badDebt = ceilDiv(remainingShares * BigInt(borrowIndex.toString()), WAD);
+    const bankBefore = await program.account.bank.fetch(solBank);
+    const insurance =
+      (await tokenBalance(solInsuranceVault)) + BigInt(bankBefore.accumulatedInsuranceFees.toString());
+    const protocolFees = BigInt(bankBefore.accumulatedProtocolFees.toString());
+
+    await settleBadDebt();
+
+    // el insurance (1 SOL + su parte del interes) y las reservas del protocolo cubren una
+    // parte, sin depositantes el resto queda registrado como deficit del bank
+    const bank = await program.account.bank.fetch(solBank);
+    expect(bank.totalBorrowShares.toNumber()).to.equal(0);
+    expect(bank.totalBorrowed.toNumber()).to.equal(0);
+    expect(bank.accumulatedProtocolFees.toNumber()).to.equal(0);
+    expect(bank.accumulatedInsuranceFees.toNumber()).to.equal(0);
+    expect(await tokenBalance(solInsuranceVault)).to.equal(0n);
+    expect(bank.unrecoveredBadDebt.toString()).to.equal(String(badDebt - insurance - protocolFees));
+
+    // la posicion de USDC sigue con el polvo
+    expect(await solPosition()).to.be.undefined;
+    expect((await usdcPosition()).depositShares.toNumber()).to.equal(1_000);
+
+    await warp(1);
+    await expectError(settleBadDebt(), "PositionNotFound");
+
+    // el fondo quedo vacio: las shares anteriores se descartan y se vuelve a emitir 1:1
+    await program.methods
+      .stakeInsurance(bn(2 * 10 ** 9))
+      .accounts({ mintAccount: mintSOL, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+    const stake = await program.account.insuranceStake.fetch(solInsuranceStake);
+    expect(stake.shares.toString()).to.equal("2000000000");
+    expect(stake.epoch.toNumber()).to.equal(1);
+    const bankAfter = await program.account.bank.fetch(solBank);
+    expect(bankAfter.totalInsuranceShares.toString()).to.equal("2000000000");
+    expect(bankAfter.insuranceEpoch.toNumber()).to.equal(1);
+
+    await setPrices({ sol: SOL_PRICE });
   });
 });
 
+// bits de Protocol.paused, ver constants.rs
+const PAUSE_DEPOSIT = 1 << 0;
+const PAUSE_WITHDRAW = 1 << 1;
+
+const WAD = 10n ** 18n;
+
+function ceilDiv(a: bigint, b: bigint) {
+  return (a + b - 1n) / b;
+}
+
+// shares de deuda que cancela un repago, redondeado hacia abajo como amount_to_borrow_shares
+function repaidShares(amount: bigint, borrowIndex: BN) {
+  return (amount * WAD) / BigInt(borrowIndex.toString());
+}
+
 function bn(n: number) {
   return new BN(n);
 }
+
+async function expectError(promise: Promise<unknown>, code: string) {
+  let error: any;
+  try {
+    await promise;
+  } catch (err) {
+    error = err;
+  }
+  expect(error, `expected ${code}`).to.not.be.undefined;
+  expect(error.error?.errorCode?.code, String(error)).to.equal(code);
+}
@@ -0,0 +1,93 @@
+import { createHash } from "crypto";
+import { PublicKey } from "@solana/web3.js";
+
+// cuentas de oracle armadas localmente, para no depender de devnet en los tests
+
+export const PYTH_RECEIVER_PROGRAM_ID = new PublicKey(
+  "rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ"
+);
+export const SWITCHBOARD_ON_DEMAND_PROGRAM_ID = new PublicKey(
+  "SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv"
+);
+
+const LAMPORTS = 1_000_000_000;
+
+function accountDiscriminator(name: string): Buffer {
+  return createHash("sha256").update(`account:${name}`).digest().subarray(0, 8);
+}
+
+// PriceUpdateV2 (borsh): discriminator, write_authority, verification_level, price_message, posted_slot
+export function mockPythPriceUpdate(
+  feedIdHex: string,
+  price: bigint,
+  conf: bigint,
+  exponent: number,
+  publishTime: bigint
+) {
+  const data = Buffer.alloc(134);
+  let offset = 0;
+
+  accountDiscriminator("PriceUpdateV2").copy(data, offset);
+  offset += 8;
+  offset += 32; // write_authority
+  data.writeUInt8(1, offset); // VerificationLevel::Full
+  offset += 1;
+
+  Buffer.from(feedIdHex, "hex").copy(data, offset);
+  offset += 32;
+  data.writeBigInt64LE(price, offset);
+  offset += 8;
+  data.writeBigUInt64LE(conf, offset);
+  offset += 8;
+  data.writeInt32LE(exponent, offset);
+  offset += 4;
+  data.writeBigInt64LE(publishTime, offset);
+  offset += 8;
+  data.writeBigInt64LE(publishTime, offset); // prev_publish_time
+  offset += 8;
+  data.writeBigInt64LE(price, offset); // ema_price
+  offset += 8;
+  data.writeBigUInt64LE(conf, offset); // ema_conf
+
+  return {
+    lamports: LAMPORTS,
+    data,
+    owner: PYTH_RECEIVER_PROGRAM_ID,
+    executable: false,
+  };
+}
+
+// PullFeedAccountData (repr(C)), solo los campos que lee el programa
+const PULL_FEED_SIZE = 3200;
+const LAST_UPDATE_TIMESTAMP_OFFSET = 2208;
+const RESULT_OFFSET = 2256;
+const RESULT_STD_DEV_OFFSET = RESULT_OFFSET + 16;
+const RESULT_SLOT_OFFSET = RESULT_OFFSET + 104;
+
+function writeI128LE(data: Buffer, value: bigint, offset: number) {
+  data.writeBigUInt64LE(BigInt.asUintN(64, value), offset);
+  data.writeBigInt64LE(value >> 64n, offset + 8);
+}
+
+// value y stdDev con 18 decimales
+export function mockSwitchboardPullFeed(
+  value: bigint,
+  stdDev: bigint,
+  lastUpdateTimestamp: bigint,
+  slot: bigint
+) {
+  const data = Buffer.alloc(8 + PULL_FEED_SIZE);
+
+  Buffer.from([196, 27, 108, 196, 10, 215, 219, 40]).copy(data, 0);
+  data.writeBigInt64LE(lastUpdateTimestamp, 8 + LAST_UPDATE_TIMESTAMP_OFFSET);
+  writeI128LE(data, value, 8 + RESULT_OFFSET);
+  writeI128LE(data, stdDev, 8 + RESULT_STD_DEV_OFFSET);
+  data.writeBigUInt64LE(slot, 8 + RESULT_SLOT_OFFSET);
+
+  return {
+    lamports: LAMPORTS,
+    data,
+    owner: SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+    executable: false,
+  };
+}
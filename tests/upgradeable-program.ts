@@ -0,0 +1,56 @@
+import { readFileSync } from "fs";
+import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+
+// el programa se carga como upgradeable, initialize_protocol valida la upgrade authority
+// contra la cuenta ProgramData
+
+export const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+// UpgradeableLoaderState::ProgramData: tag, slot, Option<upgrade_authority>
+const PROGRAM_DATA_METADATA_SIZE = 4 + 8 + 1 + 32;
+
+export function upgradeableProgramAccounts(
+  programId: PublicKey,
+  soPath: string,
+  upgradeAuthority: PublicKey
+) {
+  const [programData] = PublicKey.findProgramAddressSync(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  );
+
+  const programAccountData = Buffer.alloc(4 + 32);
+  programAccountData.writeUInt32LE(2, 0); // UpgradeableLoaderState::Program
+  programData.toBuffer().copy(programAccountData, 4);
+
+  const elf = readFileSync(soPath);
+  const programDataData = Buffer.alloc(PROGRAM_DATA_METADATA_SIZE + elf.length);
+  programDataData.writeUInt32LE(3, 0); // UpgradeableLoaderState::ProgramData
+  programDataData.writeBigUInt64LE(0n, 4);
+  programDataData.writeUInt8(1, 12);
+  upgradeAuthority.toBuffer().copy(programDataData, 13);
+  elf.copy(programDataData, PROGRAM_DATA_METADATA_SIZE);
+
+  return [
+    {
+      address: programId,
+      info: {
+        lamports: LAMPORTS_PER_SOL,
+        data: programAccountData,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        executable: true,
+      },
+    },
+    {
+      address: programData,
+      info: {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        data: programDataData,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        executable: false,
+      },
+    },
+  ];
+}